@@ -0,0 +1,219 @@
+use crate::camera::camera_relative;
+use crate::celestial_body::{kepler_newton, OrbitalParameters};
+use crate::culling::Frustum;
+use crate::framebuffer::{Color, Framebuffer};
+use crate::mesh::ObjMesh;
+use crate::renderer::Renderer;
+use crate::shaders::PlanetShader;
+use nalgebra_glm::{DVec3, Mat4, Vec3, Vec4};
+use std::f64::consts::PI;
+
+/// Tamaño proyectado (en píxeles) por debajo del cual un asteroide se dibuja como punto.
+const POINT_TIER_PIXELS: f32 = 2.0;
+/// Tamaño proyectado (en píxeles) a partir del cual se usa la malla de mayor detalle.
+const NEAR_TIER_PIXELS: f32 = 12.0;
+
+/// Datos por instancia de un asteroide.
+///
+/// Guarda la órbita ya "precocinada" (base perifocal, movimiento medio, semieje
+/// menor) para que la evaluación por cuadro se reduzca a resolver Kepler y
+/// combinar dos vectores.
+///
+/// Todo el estado orbital se lleva en `f64` como el resto de la simulación:
+/// en el cinturón de Kuiper (~3·10⁵ unidades) el paso de `f32` ronda las
+/// centésimas y se notaría al rebasar las posiciones a la cámara.
+struct AsteroidInstance {
+    radius: f32,
+    rotation_period: f64,
+    rotation_axis: Vec3,
+    albedo: Color,
+
+    semi_major_axis: f64,
+    semi_minor_axis: f64,
+    eccentricity: f64,
+    mean_motion: f64,
+    initial_mean_anomaly: f64,
+    periapsis_dir: DVec3,
+    normal_dir: DVec3,
+
+    /// Última anomalía excéntrica calculada; sirve como estimación inicial del siguiente cuadro.
+    eccentric_anomaly: f64,
+}
+
+/// Cinturón de asteroides renderizado por instancias.
+///
+/// A diferencia de los `CelestialBody`, los asteroides comparten una única malla
+/// por nivel de detalle y se dibujan en tres niveles según su tamaño en pantalla:
+/// - malla de detalle medio para los más cercanos,
+/// - malla de bajo detalle para los intermedios,
+/// - un punto (impostor) para todos los demás.
+///
+/// Las posiciones orbitales se evalúan en lote una vez por cuadro con
+/// [`AsteroidBelt::update_positions`].
+pub struct AsteroidBelt {
+    instances: Vec<AsteroidInstance>,
+    positions: Vec<DVec3>,
+    near_mesh: ObjMesh,
+    far_mesh: ObjMesh,
+}
+
+impl AsteroidBelt {
+    /// Crea un cinturón vacío con sus mallas compartidas.
+    pub fn new() -> Self {
+        Self {
+            instances: Vec::new(),
+            positions: Vec::new(),
+            near_mesh: ObjMesh::create_sphere(1.0, 12, 12),
+            far_mesh: ObjMesh::create_sphere(1.0, 6, 6),
+        }
+    }
+
+    /// Añade un asteroide al cinturón.
+    pub fn push(
+        &mut self,
+        radius: f32,
+        orbit: &OrbitalParameters,
+        rotation_period: f64,
+        rotation_axis: Vec3,
+        albedo: Color,
+    ) {
+        let (periapsis_dir, normal_dir) = orbit.perifocal_basis();
+        let e = orbit.eccentricity;
+
+        self.instances.push(AsteroidInstance {
+            radius,
+            rotation_period,
+            rotation_axis,
+            albedo,
+            semi_major_axis: orbit.semi_major_axis,
+            semi_minor_axis: orbit.semi_major_axis * (1.0 - e * e).sqrt(),
+            eccentricity: e,
+            mean_motion: if orbit.orbital_period != 0.0 {
+                2.0 * PI / orbit.orbital_period
+            } else {
+                0.0
+            },
+            initial_mean_anomaly: orbit.initial_mean_anomaly,
+            periapsis_dir,
+            normal_dir,
+            eccentric_anomaly: orbit.initial_mean_anomaly,
+        });
+        self.positions.push(DVec3::zeros());
+    }
+
+    /// Posiciones globales calculadas en la última llamada a `update_positions`.
+    pub fn positions(&self) -> &[DVec3] {
+        &self.positions
+    }
+
+    /// Evalúa en lote la posición orbital de todos los asteroides.
+    ///
+    /// Cada instancia parte de la anomalía excéntrica del cuadro anterior, por lo
+    /// que Newton-Raphson suele converger en una o dos iteraciones.
+    pub fn update_positions(&mut self, time: f64) {
+        for (instance, position) in self.instances.iter_mut().zip(self.positions.iter_mut()) {
+            let mean_anomaly = (instance.initial_mean_anomaly + instance.mean_motion * time)
+                .rem_euclid(2.0 * PI);
+
+            // Si el salto es grande (p. ej. cambio brusco de tiempo) se reinicia la estimación.
+            let mut guess = instance.eccentric_anomaly.rem_euclid(2.0 * PI);
+            if (guess - mean_anomaly).abs() > 1.0 {
+                guess = mean_anomaly;
+            }
+
+            let eccentric_anomaly = kepler_newton(instance.eccentricity, mean_anomaly, guess, 4);
+            instance.eccentric_anomaly = eccentric_anomaly;
+
+            let x = instance.semi_major_axis * (eccentric_anomaly.cos() - instance.eccentricity);
+            let y = instance.semi_minor_axis * eccentric_anomaly.sin();
+            *position = instance.periapsis_dir * x + instance.normal_dir * y;
+        }
+    }
+
+    /// Renderiza el cinturón eligiendo por instancia el nivel de detalle.
+    ///
+    /// `origin` es la posición de la cámara; las instancias se rebasan a ella
+    /// porque la matriz de vista solo contiene la rotación.
+    pub fn render(
+        &self,
+        framebuffer: &mut Framebuffer,
+        renderer: &Renderer,
+        shader: &dyn PlanetShader,
+        view_matrix: &Mat4,
+        projection_matrix: &Mat4,
+        frustum: &Frustum,
+        origin: &DVec3,
+        time: f64,
+    ) {
+        // Distancia focal en píxeles: convierte radio / profundidad en tamaño en pantalla.
+        let focal_pixels = projection_matrix[(1, 1)] * renderer.height * 0.5;
+
+        let mut near_instances = Vec::new();
+        let mut far_instances = Vec::new();
+        let mut points = Vec::new();
+
+        for (instance, world_position) in self.instances.iter().zip(self.positions.iter()) {
+            let position = &camera_relative(world_position, origin);
+            let view_pos = view_matrix * Vec4::new(position.x, position.y, position.z, 1.0);
+
+            // La cámara mira hacia -Z: todo lo que esté delante tiene z negativa.
+            let depth = -view_pos.z;
+            if depth <= instance.radius {
+                continue;
+            }
+
+            let pixel_radius = instance.radius * focal_pixels / depth;
+
+            if pixel_radius < POINT_TIER_PIXELS {
+                points.push((*position, pixel_radius * 2.0, instance.albedo));
+                continue;
+            }
+
+            if !renderer.is_in_frustum(frustum, position, instance.radius) {
+                continue;
+            }
+
+            let model_matrix = instance_model_matrix(instance, *position, time);
+            if pixel_radius < NEAR_TIER_PIXELS {
+                far_instances.push(model_matrix);
+            } else {
+                near_instances.push(model_matrix);
+            }
+        }
+
+        renderer.render_points(framebuffer, &points, view_matrix, projection_matrix);
+        renderer.render_instanced(
+            framebuffer,
+            &self.far_mesh,
+            shader,
+            &far_instances,
+            view_matrix,
+            projection_matrix,
+            time as f32,
+        );
+        renderer.render_instanced(
+            framebuffer,
+            &self.near_mesh,
+            shader,
+            &near_instances,
+            view_matrix,
+            projection_matrix,
+            time as f32,
+        );
+    }
+}
+
+/// Matriz modelo de una instancia (traslación, rotación axial y escala).
+fn instance_model_matrix(instance: &AsteroidInstance, position: Vec3, time: f64) -> Mat4 {
+    let mut transform = nalgebra_glm::translate(&Mat4::identity(), &position);
+
+    if instance.rotation_period > 0.0 {
+        let rotation_angle = ((time / instance.rotation_period).fract() * 2.0 * PI) as f32;
+        transform = nalgebra_glm::rotate(&transform, rotation_angle, &instance.rotation_axis);
+    }
+
+    nalgebra_glm::scale(
+        &transform,
+        &Vec3::new(instance.radius, instance.radius, instance.radius),
+    )
+}
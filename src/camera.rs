@@ -1,4 +1,4 @@
-use nalgebra_glm::{Vec3, Mat4, look_at};
+use nalgebra_glm::{DVec3, Vec3, Vec4, Mat4, look_at};
 use raylib::prelude::*;
 
 /// Representa la cámara principal que sigue a la nave espacial.
@@ -6,12 +6,16 @@ use raylib::prelude::*;
 /// Esta estructura implementa las funciones necesarias para un control de cámara
 /// en tercera persona o primera persona, incluyendo movimiento, aceleración,
 /// rotación con el ratón, modos de velocidad (warp) y suavizado de movimiento.
+///
+/// Las posiciones se guardan en doble precisión: a la distancia de Neptuno un
+/// `f32` ya no distingue fracciones de unidad. El render trabaja relativo a la
+/// cámara (ver [`camera_relative`]), así que la matriz de vista solo rota.
 pub struct SpaceshipCamera {
     /// Posición actual de la nave en el espacio.
-    pub position: Vec3,
+    pub position: DVec3,
     /// Punto hacia el cual la cámara apunta.
-    pub target: Vec3,
-    /// Velocidad actual de la cámara en cada eje.
+    pub target: DVec3,
+    /// Velocidad actual de la cámara en cada eje (unidades por cuadro).
     pub velocity: Vec3,
     /// Vector de dirección "forward" normalizado.
     pub forward: Vec3,
@@ -42,7 +46,7 @@ pub struct SpaceshipCamera {
     pub camera_smoothing: f32,
 
     /// Posición suavizada (interpolada) usada para evitar vibraciones.
-    smoothed_position: Vec3,
+    smoothed_position: DVec3,
     /// Rotación suavizada (yaw, pitch) usada en vista de tercera persona.
     smoothed_rotation: (f32, f32),
 
@@ -52,14 +56,17 @@ pub struct SpaceshipCamera {
     pub warp_multiplier: f32,
     /// Indica si está activo el modo “hyper warp”.
     pub hyper_warp: bool,
+    /// Vuelo newtoniano: la posición la integra
+    /// [`NewtonianShip`](crate::flight::NewtonianShip) y `update` solo orienta.
+    pub newtonian: bool,
 }
 
 impl SpaceshipCamera {
     /// Crea una nueva cámara espacial en una posición especificada.
-    pub fn new(position: Vec3) -> Self {
+    pub fn new(position: DVec3) -> Self {
         let mut camera = Self {
             position,
-            target: Vec3::zeros(),
+            target: DVec3::zeros(),
             velocity: Vec3::zeros(),
             forward: Vec3::new(0.0, 0.0, -1.0),
             right: Vec3::new(1.0, 0.0, 0.0),
@@ -78,6 +85,7 @@ impl SpaceshipCamera {
             warp_mode: false,
             warp_multiplier: 1.0,
             hyper_warp: false,
+            newtonian: false,
         };
         camera.update_vectors();
         camera
@@ -96,7 +104,7 @@ impl SpaceshipCamera {
         self.right = self.forward.cross(&Vec3::y()).normalize();
         self.up = self.right.cross(&self.forward).normalize();
 
-        self.target = self.position + self.forward;
+        self.target = self.position + self.forward.cast::<f64>();
     }
 
     /// Actualiza el estado de la cámara según la entrada del usuario y las
@@ -152,7 +160,42 @@ impl SpaceshipCamera {
             self.camera_height = (self.camera_height - 0.05).max(-2.0);
         }
 
-        // Movimiento direccional.
+        if self.newtonian {
+            self.velocity = Vec3::zeros();
+        } else {
+            let movement = self.movement_input(rl);
+            let speed_multiplier = Self::boost(rl) * self.warp_multiplier;
+
+            self.velocity += movement * self.acceleration * speed_multiplier;
+
+            // Aplicación de límite de velocidad.
+            let current_speed = self.velocity.magnitude();
+            let max_speed = self.max_speed * speed_multiplier;
+            if current_speed > max_speed {
+                self.velocity = self.velocity.normalize() * max_speed;
+            }
+
+            // Arrastre y actualización de la posición.
+            self.velocity *= self.drag;
+            self.position += self.velocity.cast::<f64>();
+        }
+
+        // Interpolación para suavizar el movimiento de cámara.
+        self.smoothed_position = self.smoothed_position
+            + (self.position - self.smoothed_position) * self.camera_smoothing as f64;
+
+        // Suavizado de rotación.
+        let yaw_diff = self.yaw - self.smoothed_rotation.0;
+        let pitch_diff = self.pitch - self.smoothed_rotation.1;
+
+        self.smoothed_rotation.0 += yaw_diff * self.camera_smoothing;
+        self.smoothed_rotation.1 += pitch_diff * self.camera_smoothing;
+
+        self.update_vectors();
+    }
+
+    /// Dirección de movimiento pedida con WASD/QE, normalizada (o nula).
+    pub fn movement_input(&self, rl: &RaylibHandle) -> Vec3 {
         let mut movement = Vec3::zeros();
         if rl.is_key_down(KeyboardKey::KEY_W) {
             movement += self.forward;
@@ -173,81 +216,73 @@ impl SpaceshipCamera {
             movement -= self.up;
         }
 
-        // Aceleración adicional al mantener Shift presionado.
-        let mut speed_multiplier =
-            if rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
-                3.0
-            } else {
-                1.0
-            };
-
-        speed_multiplier *= self.warp_multiplier;
-
         if movement.magnitude() > 0.0 {
-            movement = movement.normalize();
-            self.velocity += movement * self.acceleration * speed_multiplier;
+            movement.normalize()
+        } else {
+            movement
         }
+    }
 
-        // Aplicación de límite de velocidad.
-        let current_speed = self.velocity.magnitude();
-        let max_speed = self.max_speed * speed_multiplier;
-        if current_speed > max_speed {
-            self.velocity = self.velocity.normalize() * max_speed;
+    /// Aceleración adicional al mantener Shift presionado.
+    pub fn boost(rl: &RaylibHandle) -> f32 {
+        if rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
+            3.0
+        } else {
+            1.0
         }
+    }
 
-        // Arrastre y actualización de la posición.
-        self.velocity *= self.drag;
-        self.position += self.velocity;
-
-        // Interpolación para suavizar el movimiento de cámara.
-        self.smoothed_position = self.smoothed_position
-            + (self.position - self.smoothed_position) * self.camera_smoothing;
-
-        // Suavizado de rotación.
-        let yaw_diff = self.yaw - self.smoothed_rotation.0;
-        let pitch_diff = self.pitch - self.smoothed_rotation.1;
-
-        self.smoothed_rotation.0 += yaw_diff * self.camera_smoothing;
-        self.smoothed_rotation.1 += pitch_diff * self.camera_smoothing;
-
+    /// Lleva la nave a `position` sin suavizado: en vuelo newtoniano la
+    /// posición es la integrada y el retraso del suavizado se notaría a
+    /// velocidades orbitales.
+    pub fn follow(&mut self, position: DVec3) {
+        self.position = position;
+        self.smoothed_position = position;
         self.update_vectors();
     }
 
-    /// Devuelve la matriz de vista (`Mat4`) correspondiente a la posición y orientación actuales.
+    /// Devuelve la matriz de vista (`Mat4`) correspondiente a la orientación actual.
+    ///
+    /// La cámara queda en el origen: todo lo que se dibuje debe pasarse antes
+    /// por [`camera_relative`] con la posición de [`Self::get_camera_position`].
     pub fn get_view_matrix(&self) -> Mat4 {
         if self.third_person {
-            let smoothed_forward = Vec3::new(
-                self.smoothed_rotation.0.cos() * self.smoothed_rotation.1.cos(),
-                self.smoothed_rotation.1.sin(),
-                self.smoothed_rotation.0.sin() * self.smoothed_rotation.1.cos(),
-            )
-            .normalize();
+            let smoothed_forward = self.smoothed_forward();
 
             let smoothed_right = smoothed_forward.cross(&Vec3::y()).normalize();
             let smoothed_up = smoothed_right.cross(&smoothed_forward).normalize();
 
-            // Cámara colocada detrás y encima de la nave.
+            // Cámara colocada detrás y encima de la nave; el objetivo se expresa
+            // relativo a la propia cámara para no perder precisión.
             let camera_offset =
                 -smoothed_forward * self.camera_distance + smoothed_up * self.camera_height;
+            let look_target = smoothed_forward * 2.0 - camera_offset;
 
-            let camera_pos = self.smoothed_position + camera_offset;
-            let look_target = self.smoothed_position + smoothed_forward * 2.0;
-
-            look_at(&camera_pos, &look_target, &smoothed_up)
+            look_at(&Vec3::zeros(), &look_target, &smoothed_up)
         } else {
-            look_at(&self.position, &self.target, &self.up)
+            look_at(&Vec3::zeros(), &self.forward, &self.up)
         }
     }
 
+    /// Dirección de avance suavizada usada en tercera persona.
+    fn smoothed_forward(&self) -> Vec3 {
+        Vec3::new(
+            self.smoothed_rotation.0.cos() * self.smoothed_rotation.1.cos(),
+            self.smoothed_rotation.1.sin(),
+            self.smoothed_rotation.0.sin() * self.smoothed_rotation.1.cos(),
+        )
+        .normalize()
+    }
+
     /// Calcula el cuerpo celeste más cercano a la cámara.
     pub fn get_nearest_body_distance(
         &self,
-        bodies_positions: &[Vec3],
+        bodies_positions: &[DVec3],
     ) -> Option<(usize, f32)> {
         bodies_positions
             .iter()
             .enumerate()
-            .map(|(i, pos)| (i, (pos - self.position).magnitude()))
+            .map(|(i, pos)| (i, (pos - self.position).magnitude() as f32))
             .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
     }
 
@@ -270,14 +305,9 @@ impl SpaceshipCamera {
     }
 
     /// Retorna la posición actual de la cámara, dependiendo del modo de vista.
-    pub fn get_camera_position(&self) -> Vec3 {
+    pub fn get_camera_position(&self) -> DVec3 {
         if self.third_person {
-            let smoothed_forward = Vec3::new(
-                self.smoothed_rotation.0.cos() * self.smoothed_rotation.1.cos(),
-                self.smoothed_rotation.1.sin(),
-                self.smoothed_rotation.0.sin() * self.smoothed_rotation.1.cos(),
-            )
-            .normalize();
+            let smoothed_forward = self.smoothed_forward();
 
             let smoothed_right = smoothed_forward.cross(&Vec3::y()).normalize();
             let smoothed_up = smoothed_right.cross(&smoothed_forward).normalize();
@@ -285,7 +315,7 @@ impl SpaceshipCamera {
             let camera_offset =
                 -smoothed_forward * self.camera_distance + smoothed_up * self.camera_height;
 
-            self.smoothed_position + camera_offset
+            self.smoothed_position + camera_offset.cast::<f64>()
         } else {
             self.position
         }
@@ -295,9 +325,9 @@ impl SpaceshipCamera {
     ///
     /// Si se detecta una intersección, la cámara es empujada hacia fuera del planeta
     /// y se ajusta la velocidad para evitar penetraciones.
-    pub fn check_collisions(&mut self, bodies: &[(Vec3, f32)]) {
+    pub fn check_collisions(&mut self, bodies: &[(DVec3, f32)]) {
         for (body_pos, body_radius) in bodies {
-            let to_body = *body_pos - self.position;
+            let to_body = (*body_pos - self.position).cast::<f32>();
             let distance = to_body.magnitude();
             let safe_distance = body_radius * 2.5;
 
@@ -305,7 +335,7 @@ impl SpaceshipCamera {
                 let overlap = safe_distance - distance;
                 if overlap > 0.0 {
                     let rejection_dir = -to_body.normalize();
-                    self.position += rejection_dir * (overlap + 1.0);
+                    self.position += (rejection_dir * (overlap + 1.0)).cast::<f64>();
 
                     let velocity_toward_body = self.velocity.dot(&to_body.normalize());
                     if velocity_toward_body > 0.0 {
@@ -323,10 +353,10 @@ impl SpaceshipCamera {
     /// Devuelve información sobre una colisión potencial inminente para propósitos de alerta.
     pub fn get_collision_warning(
         &self,
-        bodies: &[(Vec3, f32)],
+        bodies: &[(DVec3, f32)],
     ) -> Option<(usize, f32, &str)> {
         for (i, (body_pos, body_radius)) in bodies.iter().enumerate() {
-            let distance = (body_pos - self.position).magnitude();
+            let distance = (body_pos - self.position).magnitude() as f32;
             let warning_distance = body_radius * 4.0;
 
             if distance < warning_distance {
@@ -344,6 +374,27 @@ impl SpaceshipCamera {
         None
     }
 
+    /// Orienta la nave para que mire hacia un punto del espacio.
+    ///
+    /// Se usa para "fijar" un cuerpo seleccionado: la rotación suavizada de la
+    /// cámara en tercera persona sigue al nuevo `yaw`/`pitch` sin saltos.
+    pub fn look_at_point(&mut self, point: DVec3) {
+        let direction = (point - self.position).cast::<f32>();
+        if direction.magnitude() < 1e-3 {
+            return;
+        }
+
+        let direction = direction.normalize();
+        // Elegir el ángulo equivalente más cercano para que el suavizado no dé la vuelta larga.
+        let target_yaw = direction.z.atan2(direction.x);
+        let delta = (target_yaw - self.yaw + std::f32::consts::PI)
+            .rem_euclid(2.0 * std::f32::consts::PI)
+            - std::f32::consts::PI;
+        self.yaw += delta;
+        self.pitch = direction.y.asin().clamp(-1.4, 1.4);
+        self.update_vectors();
+    }
+
     /// Sincroniza los valores suavizados con la posición y rotación actual.
     pub fn sync_smoothed_position(&mut self) {
         self.smoothed_position = self.position;
@@ -352,15 +403,13 @@ impl SpaceshipCamera {
 
     /// Genera una matriz modelo para posicionar la nave en el espacio de cámara
     /// durante el renderizado en tercera persona.
-    pub fn get_ship_model_matrix_fixed(&self, base_scale: f32) -> Mat4 {
+    ///
+    /// La traslación se calcula en doble precisión relativa a `origin` (la
+    /// posición de la cámara), así la nave no tiembla lejos del Sol.
+    pub fn get_ship_model_matrix_fixed(&self, base_scale: f32, origin: &DVec3) -> Mat4 {
         let mut transform = Mat4::identity();
 
-        let forward = Vec3::new(
-            self.smoothed_rotation.0.cos() * self.smoothed_rotation.1.cos(),
-            self.smoothed_rotation.1.sin(),
-            self.smoothed_rotation.0.sin() * self.smoothed_rotation.1.cos(),
-        )
-        .normalize();
+        let forward = self.smoothed_forward();
 
         let right = forward.cross(&Vec3::y()).normalize();
         let up = right.cross(&forward).normalize();
@@ -370,10 +419,8 @@ impl SpaceshipCamera {
         let offset_up = 0.5;
         let offset_right = 0.0;
 
-        let ship_position = self.smoothed_position
-            + forward * offset_forward
-            + up * offset_up
-            + right * offset_right;
+        let ship_offset = forward * offset_forward + up * offset_up + right * offset_right;
+        let ship_position = camera_relative(&(self.smoothed_position + ship_offset.cast::<f64>()), origin);
 
         transform = nalgebra_glm::translate(&transform, &ship_position);
 
@@ -390,4 +437,26 @@ impl SpaceshipCamera {
 
         transform
     }
-}
\ No newline at end of file
+}
+/// Traslada un punto global al sistema centrado en la cámara.
+///
+/// La resta se hace en `f64` y solo el resultado, ya pequeño cerca de la
+/// cámara, se reduce a `f32` para el renderer.
+pub fn camera_relative(point: &DVec3, origin: &DVec3) -> Vec3 {
+    (point - origin).cast::<f32>()
+}
+
+/// Proyecta un punto relativo a la cámara a píxeles de una pantalla de
+/// `width` × `height`, para colocar etiquetas sobre la vista 3D. Devuelve
+/// `None` si queda detrás de la cámara o fuera de la pantalla.
+pub fn project_to_screen(point: &Vec3, view_projection: &Mat4, width: f32, height: f32) -> Option<(i32, i32)> {
+    let clip = view_projection * Vec4::new(point.x, point.y, point.z, 1.0);
+    if clip.w <= 1e-6 {
+        return None;
+    }
+    let (x, y) = (clip.x / clip.w, clip.y / clip.w);
+    if x.abs() > 1.0 || y.abs() > 1.0 {
+        return None;
+    }
+    Some((((x + 1.0) * 0.5 * width) as i32, ((1.0 - y) * 0.5 * height) as i32))
+}
@@ -1,8 +1,10 @@
-use nalgebra_glm::{Vec3, Mat4, rotate_vec3};
-use std::f32::consts::PI;
+use nalgebra_glm::{DVec3, Vec3, Mat4, rotate_vec3};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
 
 /// Enumeración que define los tipos posibles de cuerpos celestes.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CelestialType {
     /// Estrella principal (ej. el Sol).
     Star,
@@ -14,30 +16,266 @@ pub enum CelestialType {
     Asteroid,
 }
 
+/// Shader de superficie con el que se pinta un cuerpo.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SurfaceShader {
+    Sun,
+    Mercury,
+    Venus,
+    Earth,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+    /// Planeta rocoso genérico.
+    Rocky,
+    Moon,
+    Asteroid,
+}
+
+impl SurfaceShader {
+    /// Shader por defecto para un tipo de cuerpo.
+    pub fn default_for(body_type: CelestialType) -> Self {
+        match body_type {
+            CelestialType::Star => SurfaceShader::Sun,
+            CelestialType::Planet => SurfaceShader::Rocky,
+            CelestialType::Moon => SurfaceShader::Moon,
+            CelestialType::Asteroid => SurfaceShader::Asteroid,
+        }
+    }
+}
+
+/// Sistema de anillos alrededor de un cuerpo.
+#[derive(Debug, Clone)]
+pub struct Rings {
+    /// Radio interior, en múltiplos del radio del cuerpo.
+    pub inner_radius: f32,
+    /// Radio exterior, en múltiplos del radio del cuerpo.
+    pub outer_radius: f32,
+    /// Inclinación del plano de los anillos respecto al ecuador del cuerpo
+    /// (radianes).
+    pub tilt: f32,
+    /// Eje alrededor del cual se aplica la inclinación.
+    pub tilt_axis: Vec3,
+}
+
+/// Rotación de un cuerpo sobre su eje.
+///
+/// El eje se inclina `obliquity` respecto a la normal del plano orbital (el
+/// eje Y global si el cuerpo no orbita) hacia `axis_longitude`, medida en el
+/// plano orbital desde la proyección del eje X global, como la longitud
+/// eclíptica. El meridiano origen se mide desde el nodo ascendente del
+/// ecuador sobre el plano orbital. Los ángulos van en radianes y los
+/// tiempos en días.
+#[derive(Debug, Clone, Default)]
+pub struct RotationModel {
+    /// Período sidéreo; negativo si el giro es retrógrado (Venus, Urano) y
+    /// 0 si el cuerpo no gira.
+    pub period: f64,
+    /// Inclinación del eje respecto a la normal de la órbita. Con período
+    /// negativo, una oblicuidad ε equivale a 180° - ε con período positivo.
+    pub obliquity: f64,
+    /// Dirección hacia la que se inclina el eje.
+    pub axis_longitude: f64,
+    /// Ángulo del meridiano origen en J2000.
+    pub prime_meridian: f64,
+    /// Acoplamiento de marea: el meridiano origen mira siempre al cuerpo
+    /// padre y el período solo es informativo.
+    pub tidally_locked: bool,
+    /// Período de precesión del eje alrededor de la normal de la órbita;
+    /// negativo si es retrógrada (como la de la Tierra) y 0 si no hay.
+    pub precession_period: f64,
+}
+
+impl RotationModel {
+    /// Eje de giro en `time` (vector unitario en coordenadas globales).
+    pub fn spin_axis(&self, time: f64, orbit: Option<&OrbitalParameters>) -> DVec3 {
+        let (normal, reference, side) = orbit_plane(orbit, time);
+        let mut longitude = self.axis_longitude;
+        if self.precession_period != 0.0 {
+            longitude += (time / self.precession_period).fract() * 2.0 * PI;
+        }
+        let tilt_direction = reference * longitude.cos() + side * longitude.sin();
+        normal * self.obliquity.cos() + tilt_direction * self.obliquity.sin()
+    }
+
+    /// Ángulo girado desde el meridiano de referencia en `time`. `orbit` da
+    /// la dirección al padre para el acoplamiento de marea.
+    pub fn spin_angle(&self, time: f64, orbit: Option<&OrbitalParameters>) -> f64 {
+        let locked_orbit = orbit.filter(|_| self.tidally_locked);
+        if let Some(orbit) = locked_orbit {
+            let (equator_x, _, equator_z) = self.equatorial_frame(time, Some(orbit));
+            let to_parent = -orbit.get_position(time);
+            return (-to_parent.dot(&equator_z)).atan2(to_parent.dot(&equator_x)) + self.prime_meridian;
+        }
+        if self.period == 0.0 {
+            return self.prime_meridian;
+        }
+        // El ángulo se reduce a una vuelta en f64 antes de pasar a f32.
+        self.prime_meridian + (time / self.period).fract() * 2.0 * PI
+    }
+
+    /// Orientación del cuerpo en `time`: inclinación del eje, precesión y
+    /// giro. El eje Y del modelo queda sobre el eje de giro.
+    pub fn orientation(&self, time: f64, orbit: Option<&OrbitalParameters>) -> Mat4 {
+        let (equator_x, axis, equator_z) = self.equatorial_frame(time, orbit);
+        let angle = self.spin_angle(time, orbit);
+        let (sin, cos) = angle.sin_cos();
+
+        // Giro sobre el eje: el X local va a (cos, 0, -sin) y el Z a (sin, 0, cos).
+        let x = (equator_x * cos - equator_z * sin).cast::<f32>();
+        let y = axis.cast::<f32>();
+        let z = (equator_x * sin + equator_z * cos).cast::<f32>();
+        Mat4::new(
+            x.x, y.x, z.x, 0.0,
+            x.y, y.y, z.y, 0.0,
+            x.z, y.z, z.z, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        )
+    }
+
+    /// Base del ecuador sin girar: nodo ascendente sobre el plano orbital,
+    /// eje de giro y el tercero que completa la base dextrógira.
+    fn equatorial_frame(&self, time: f64, orbit: Option<&OrbitalParameters>) -> (DVec3, DVec3, DVec3) {
+        let axis = self.spin_axis(time, orbit);
+        let (normal, reference, _) = orbit_plane(orbit, time);
+        let node = normal.cross(&axis);
+        let node = if node.magnitude() > 1e-9 { node.normalize() } else { reference };
+        (node, axis, node.cross(&axis))
+    }
+}
+
+/// Normal del plano orbital y dos direcciones sobre él: la proyección del eje
+/// X global y la que va 90° por delante en el sentido del movimiento.
+fn orbit_plane(orbit: Option<&OrbitalParameters>, time: f64) -> (DVec3, DVec3, DVec3) {
+    let normal = orbit
+        .map(|params| {
+            let (p, q) = params.at_time(time).perifocal_basis();
+            p.cross(&q).normalize()
+        })
+        .unwrap_or_else(DVec3::y);
+
+    let projected = DVec3::x() - normal * normal.x;
+    let reference = if projected.magnitude() > 1e-9 {
+        projected.normalize()
+    } else {
+        DVec3::z()
+    };
+    (normal, reference, normal.cross(&reference))
+}
+
+/// Constante de gravitación universal en km³/(kg·s²).
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.6743e-20;
+
+/// Propiedades físicas reales de un cuerpo.
+///
+/// Se expresan en unidades físicas (km, kg, K), no en unidades de
+/// simulación: el radio visual de [`CelestialBody::radius`] está exagerado
+/// para que los cuerpos se vean a escala del sistema.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicalProperties {
+    /// Parámetro gravitacional estándar GM (km³/s²).
+    pub gm: f64,
+    /// Densidad media (g/cm³).
+    pub mean_density: f64,
+    /// Radio ecuatorial (km).
+    pub equatorial_radius: f64,
+    /// Radio polar (km).
+    pub polar_radius: f64,
+    /// Albedo geométrico.
+    pub albedo: f64,
+    /// Temperatura superficial media (K); efectiva para estrellas y a 1 bar
+    /// para los gigantes gaseosos.
+    pub surface_temperature: f64,
+    /// Composición principal de la atmósfera (vacío si no tiene).
+    pub atmosphere: String,
+    /// Descripción breve para la interfaz.
+    pub description: String,
+}
+
+impl PhysicalProperties {
+    /// Masa (kg) derivada de GM, que se conoce con mucha más precisión.
+    pub fn mass(&self) -> f64 {
+        self.gm / GRAVITATIONAL_CONSTANT
+    }
+}
+
+/// Días en un siglo juliano, unidad de tiempo de las tasas seculares.
+pub const DAYS_PER_JULIAN_CENTURY: f64 = 36525.0;
+
+/// Margen alrededor de e = 1 dentro del cual una órbita abierta se trata
+/// como parábola; cerca de ahí el semieje de la hipérbola diverge.
+const PARABOLIC_TOLERANCE: f64 = 1e-6;
+
+/// Hasta dónde se dibuja una órbita abierta, en múltiplos de la distancia
+/// del periapsis.
+const OPEN_ORBIT_EXTENT: f64 = 20.0;
+
+/// Variación lineal de los elementos orbitales por siglo juliano.
+///
+/// Sigue el modelo de las "posiciones aproximadas de los planetas" de JPL:
+/// cada elemento vale `valor_J2000 + tasa * T`, con `T` en siglos desde J2000.
+/// Las unidades son las mismas que las del elemento correspondiente en
+/// [`OrbitalParameters`] (unidades de simulación y radianes).
+#[derive(Clone, Default)]
+pub struct SecularRates {
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub longitude_of_ascending_node: f64,
+    pub argument_of_periapsis: f64,
+}
+
 /// Representa los parámetros orbitales de un cuerpo celeste según las leyes de Kepler.
 ///
-/// Determina la posición relativa de un cuerpo en su órbita elíptica durante la simulación.
+/// Determina la posición relativa de un cuerpo en su órbita durante la simulación.
+/// Las órbitas cerradas (e < 1) avanzan según su período; las abiertas
+/// (hipérbolas y parábolas, como cometas en escape u objetos interestelares)
+/// no tienen período y se describen con el paso por el periapsis y el GM
+/// del cuerpo central.
+///
+/// Todo el estado orbital es de doble precisión: tras sesiones largas o con
+/// mucha aceleración temporal, `n * t` en `f32` ya no resuelve fracciones de
+/// vuelta y los planetas avanzan a saltos.
 #[derive(Clone)]
 pub struct OrbitalParameters {
     /// Semieje mayor de la órbita (en unidades arbitrarias).
-    pub semi_major_axis: f32,
-    /// Excentricidad orbital (0 = circular, <1 = elíptica).
-    pub eccentricity: f32,
+    ///
+    /// En órbitas abiertas guarda la distancia del periapsis `q`: el semieje
+    /// de la parábola no es finito.
+    pub semi_major_axis: f64,
+    /// Excentricidad orbital (0 = circular, <1 = elíptica, 1 = parabólica,
+    /// >1 = hiperbólica).
+    pub eccentricity: f64,
     /// Inclinación orbital respecto al plano de referencia (en radianes).
-    pub inclination: f32,
+    pub inclination: f64,
     /// Longitud del nodo ascendente (Ω, en radianes).
-    pub longitude_of_ascending_node: f32,
+    pub longitude_of_ascending_node: f64,
     /// Argumento del periapsis (ω, en radianes).
-    pub argument_of_periapsis: f32,
-    /// Período orbital (en segundos simulados).
-    pub orbital_period: f32,
+    pub argument_of_periapsis: f64,
+    /// Período orbital (días).
+    pub orbital_period: f64,
     /// Anomalía media inicial (posición angular inicial en la órbita).
-    pub initial_mean_anomaly: f32,
+    pub initial_mean_anomaly: f64,
+    /// GM del cuerpo central (unidades³/día²). Solo lo usan las órbitas
+    /// abiertas, que no tienen período del que deducir el movimiento medio.
+    pub gravitational_parameter: f64,
+    /// Instante del paso por el periapsis (días). En órbitas abiertas hace
+    /// el papel de la anomalía media inicial.
+    pub time_of_periapsis: f64,
+    /// Deriva secular de los elementos; `None` para órbitas fijas.
+    ///
+    /// Con tasas, `time` se interpreta como días desde J2000 y los elementos
+    /// anteriores son los de esa época.
+    pub secular_rates: Option<SecularRates>,
 }
 
 impl OrbitalParameters {
     /// Crea una órbita circular simple con un radio y período definidos.
-    pub fn circular(radius: f32, period: f32) -> Self {
+    pub fn circular(radius: f64, period: f64) -> Self {
         Self {
             semi_major_axis: radius,
             eccentricity: 0.0,
@@ -46,45 +284,350 @@ impl OrbitalParameters {
             argument_of_periapsis: 0.0,
             orbital_period: period,
             initial_mean_anomaly: 0.0,
+            gravitational_parameter: 0.0,
+            time_of_periapsis: 0.0,
+            secular_rates: None,
+        }
+    }
+
+    /// Indica si la órbita es abierta (parabólica o hiperbólica).
+    pub fn is_open(&self) -> bool {
+        self.eccentricity >= 1.0
+    }
+
+    /// Distancia mínima al cuerpo central.
+    pub fn periapsis_distance(&self) -> f64 {
+        if self.is_open() {
+            self.semi_major_axis
+        } else {
+            self.semi_major_axis * (1.0 - self.eccentricity)
+        }
+    }
+
+    /// Construye la órbita a partir de los elementos medios de JPL.
+    ///
+    /// # Parámetros
+    /// * `elements`: `[a, e, I, L, ϖ, Ω]` en J2000 (a en UA, ángulos en grados).
+    /// * `rates`: las mismas magnitudes por siglo juliano.
+    /// * `au`: unidades de simulación por unidad astronómica.
+    ///
+    /// El argumento del periapsis es `ω = ϖ - Ω` y la anomalía media `M = L - ϖ`;
+    /// el período sale de la tasa de `M`, de modo que `L` avanza exactamente
+    /// como en la tabla.
+    pub fn from_mean_elements(elements: [f64; 6], rates: [f64; 6], au: f64) -> Self {
+        let [a, e, inclination, mean_longitude, perihelion, node] = elements;
+        let [a_rate, e_rate, inclination_rate, mean_longitude_rate, perihelion_rate, node_rate] =
+            rates;
+
+        let mean_anomaly_rate = mean_longitude_rate - perihelion_rate; // grados por siglo
+
+        Self {
+            semi_major_axis: a * au,
+            eccentricity: e,
+            inclination: inclination.to_radians(),
+            longitude_of_ascending_node: node.to_radians(),
+            argument_of_periapsis: (perihelion - node).to_radians(),
+            orbital_period: 360.0 / mean_anomaly_rate * DAYS_PER_JULIAN_CENTURY,
+            initial_mean_anomaly: (mean_longitude - perihelion).to_radians(),
+            gravitational_parameter: 0.0,
+            time_of_periapsis: 0.0,
+            secular_rates: Some(SecularRates {
+                semi_major_axis: a_rate * au,
+                eccentricity: e_rate,
+                inclination: inclination_rate.to_radians(),
+                longitude_of_ascending_node: node_rate.to_radians(),
+                argument_of_periapsis: (perihelion_rate - node_rate).to_radians(),
+            }),
+        }
+    }
+
+    /// Elementos osculantes en `time` (días desde J2000), ya sin tasas.
+    ///
+    /// Para órbitas sin deriva secular devuelve una copia sin cambios.
+    pub fn at_time(&self, time: f64) -> OrbitalParameters {
+        let mut elements = self.clone();
+        if let Some(rates) = elements.secular_rates.take() {
+            let centuries = time / DAYS_PER_JULIAN_CENTURY;
+            elements.semi_major_axis += rates.semi_major_axis * centuries;
+            elements.eccentricity += rates.eccentricity * centuries;
+            elements.inclination += rates.inclination * centuries;
+            elements.longitude_of_ascending_node += rates.longitude_of_ascending_node * centuries;
+            elements.argument_of_periapsis += rates.argument_of_periapsis * centuries;
         }
+        elements
     }
 
     /// Calcula la posición orbital tridimensional de un objeto en un tiempo dado.
     ///
-    /// Implementa las ecuaciones de Kepler para órbitas elípticas.
+    /// Implementa las ecuaciones de Kepler para órbitas elípticas, y sus
+    /// equivalentes hiperbólica y parabólica para las abiertas.
     ///
     /// # Parámetros
     /// * `time`: Tiempo actual de simulación.
     ///
     /// # Retorna
     /// Vector 3D con la posición resultante.
-    pub fn get_position(&self, time: f32) -> Vec3 {
-        if self.orbital_period == 0.0 {
-            return Vec3::zeros(); // Objeto estacionario (por ejemplo, el Sol).
+    pub fn get_position(&self, time: f64) -> DVec3 {
+        self.get_state(time).0
+    }
+
+    /// Posición y velocidad (unidades/día) relativas al cuerpo central en `time`.
+    ///
+    /// La velocidad sale de la forma cerrada `v = √(μ/p)·(-sin ν, e + cos ν)`
+    /// en el plano orbital, válida para las tres cónicas. Con tasas seculares
+    /// es la velocidad sobre la elipse osculante, sin la deriva de los elementos.
+    pub fn get_state(&self, time: f64) -> (DVec3, DVec3) {
+        if self.secular_rates.is_some() {
+            return self.at_time(time).get_state(time);
         }
 
-        // Cálculo de la anomalía media M = n * t, donde n = 2π / T
-        let mean_motion = 2.0 * PI / self.orbital_period;
-        let mean_anomaly = self.initial_mean_anomaly + mean_motion * time;
+        // Objeto estacionario (por ejemplo, el Sol).
+        let Some(true_anomaly) = self.true_anomaly(time) else {
+            return (DVec3::zeros(), DVec3::zeros());
+        };
+
+        let e = self.eccentricity;
+        let semi_latus_rectum = self.semi_latus_rectum();
+        let radius = semi_latus_rectum / (1.0 + e * true_anomaly.cos());
+        // Un período negativo recorre la órbita en sentido contrario.
+        let direction = if !self.is_open() && self.orbital_period < 0.0 { -1.0 } else { 1.0 };
+        let speed = direction * (self.central_gm() / semi_latus_rectum).sqrt();
 
-        // Resolución numérica de la ecuación de Kepler: E - e sin(E) = M
-        let eccentric_anomaly = self.solve_kepler(mean_anomaly);
+        // Proyección sobre la base perifocal ya rotada al espacio global.
+        let (p, q) = self.perifocal_basis();
+        let (sin_nu, cos_nu) = true_anomaly.sin_cos();
+        let position = (p * cos_nu + q * sin_nu) * radius;
+        let velocity = (p * -sin_nu + q * (e + cos_nu)) * speed;
+        (position, velocity)
+    }
+
+    /// Anomalía verdadera ν en `time` (radianes), o `None` si el cuerpo no
+    /// se mueve.
+    pub fn true_anomaly(&self, time: f64) -> Option<f64> {
+        if self.secular_rates.is_some() {
+            return self.at_time(time).true_anomaly(time);
+        }
 
-        // Coordenadas en el plano orbital
-        let a = self.semi_major_axis;
         let e = self.eccentricity;
-        let x = a * (eccentric_anomaly.cos() - e);
-        let y = a * (1.0 - e * e).sqrt() * eccentric_anomaly.sin();
+        if self.is_open() {
+            let q = self.semi_major_axis;
+            let mu = self.gravitational_parameter;
+            if q <= 0.0 || mu <= 0.0 {
+                return None;
+            }
 
-        // Posición inicial en el plano orbital
-        let mut pos = Vec3::new(x, 0.0, y);
+            let elapsed = time - self.time_of_periapsis;
+            if e - 1.0 < PARABOLIC_TOLERANCE {
+                // Ecuación de Barker: D + D³/3 = √(μ / 2q³)·Δt, con D = tan(ν/2).
+                let d = solve_barker((mu / (2.0 * q.powi(3))).sqrt() * elapsed);
+                Some(2.0 * d.atan())
+            } else {
+                // Ecuación de Kepler hiperbólica: e sinh(H) - H = M, con |a| = q / (e - 1).
+                let a = q / (e - 1.0);
+                let mean_anomaly = (mu / a.powi(3)).sqrt() * elapsed;
+                let h = solve_hyperbolic_kepler(e, mean_anomaly);
+                Some(2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (h / 2.0).tanh()).atan())
+            }
+        } else {
+            if self.orbital_period == 0.0 {
+                return None;
+            }
+
+            // Cálculo de la anomalía media M = n * t, donde n = 2π / T.
+            // Se reduce a [0, 2π) para que Newton-Raphson parta de un valor acotado.
+            let mean_motion = 2.0 * PI / self.orbital_period;
+            let mean_anomaly =
+                (self.initial_mean_anomaly + mean_motion * time).rem_euclid(2.0 * PI);
+
+            // Resolución numérica de la ecuación de Kepler: E - e sin(E) = M
+            let half_e = self.solve_kepler(mean_anomaly) / 2.0;
+            Some(2.0 * ((1.0 + e).sqrt() * half_e.sin()).atan2((1.0 - e).sqrt() * half_e.cos()))
+        }
+    }
 
-        // Aplicación de rotaciones orbitales en orden:
-        pos = rotate_vec3(&pos, self.argument_of_periapsis, &Vec3::y()); // ω
-        pos = rotate_vec3(&pos, self.inclination, &Vec3::x()); // i
-        pos = rotate_vec3(&pos, self.longitude_of_ascending_node, &Vec3::y()); // Ω
+    /// GM del cuerpo central (unidades³/día²): el indicado en las órbitas
+    /// abiertas y el que implica la tercera ley de Kepler (n²a³) en las cerradas.
+    pub fn central_gm(&self) -> f64 {
+        if self.is_open() {
+            self.gravitational_parameter
+        } else if self.orbital_period == 0.0 {
+            0.0
+        } else {
+            let mean_motion = 2.0 * PI / self.orbital_period;
+            mean_motion * mean_motion * self.semi_major_axis.powi(3)
+        }
+    }
 
-        pos
+    /// Semilado recto `p`, la escala común a las tres cónicas: `r = p / (1 + e cos ν)`.
+    pub fn semi_latus_rectum(&self) -> f64 {
+        if self.is_open() {
+            self.semi_major_axis * (1.0 + self.eccentricity)
+        } else {
+            self.semi_major_axis * (1.0 - self.eccentricity * self.eccentricity)
+        }
+    }
+
+    /// Distancia máxima al cuerpo central; `None` en órbitas abiertas.
+    pub fn apoapsis_distance(&self) -> Option<f64> {
+        (!self.is_open()).then_some(self.semi_major_axis * (1.0 + self.eccentricity))
+    }
+
+    /// Energía orbital específica `v²/2 - μ/r` (unidades²/día²): negativa en
+    /// órbitas ligadas, nula en la parábola y positiva en la hipérbola.
+    pub fn specific_energy(&self) -> f64 {
+        let mu = self.central_gm();
+        if !self.is_open() {
+            -mu / (2.0 * self.semi_major_axis)
+        } else if self.eccentricity - 1.0 < PARABOLIC_TOLERANCE {
+            0.0
+        } else {
+            mu * (self.eccentricity - 1.0) / (2.0 * self.semi_major_axis)
+        }
+    }
+
+    /// Elementos de la cónica que sigue un cuerpo con `position` y `velocity`
+    /// (unidades y unidades/día, relativas al cuerpo central) en `time`.
+    ///
+    /// Devuelve `None` si no hay órbita definida: GM no positivo, el cuerpo
+    /// en el centro o una trayectoria radial (momento angular nulo). En
+    /// órbitas ecuatoriales el nodo se toma en Ω = 0 y en las circulares el
+    /// periapsis en el nodo.
+    pub fn from_state_vectors(
+        position: &DVec3,
+        velocity: &DVec3,
+        gravitational_parameter: f64,
+        time: f64,
+    ) -> Option<Self> {
+        const EPSILON: f64 = 1e-10;
+        let mu = gravitational_parameter;
+
+        // Al marco eclíptico (Z hacia el polo), inverso del mapeo de `perifocal_basis`.
+        let r = DVec3::new(position.x, -position.z, position.y);
+        let v = DVec3::new(velocity.x, -velocity.z, velocity.y);
+        let radius = r.magnitude();
+        let momentum = r.cross(&v);
+        let h = momentum.magnitude();
+        if mu <= 0.0 || radius < EPSILON || h < EPSILON * radius * v.magnitude().max(1.0) {
+            return None;
+        }
+
+        let eccentricity_vector = (r * (v.magnitude_squared() - mu / radius) - v * r.dot(&v)) / mu;
+        // Cerca de e = 1 el redondeo puede dejar una parábola como una elipse
+        // de semieje enorme: dentro del margen se trata como parábola.
+        let e = eccentricity_vector.magnitude();
+        let e = if (e - 1.0).abs() < PARABOLIC_TOLERANCE { 1.0 } else { e };
+        let inclination = (momentum.z / h).clamp(-1.0, 1.0).acos();
+
+        // Línea de nodos n = k × h y ángulos medidos desde ella (o desde X si es ecuatorial).
+        let node = DVec3::new(-momentum.y, momentum.x, 0.0);
+        let equatorial = node.magnitude() < EPSILON * h;
+        let longitude_of_ascending_node = if equatorial { 0.0 } else { node.y.atan2(node.x) };
+        let node_direction = if equatorial {
+            DVec3::x()
+        } else {
+            node.normalize()
+        };
+        // Eje a 90° del nodo dentro del plano orbital, en el sentido del movimiento.
+        let in_plane_normal = (momentum / h).cross(&node_direction);
+        let angle_from_node = |w: &DVec3| w.dot(&in_plane_normal).atan2(w.dot(&node_direction));
+
+        let circular = e < EPSILON;
+        let argument_of_periapsis = if circular {
+            0.0
+        } else {
+            angle_from_node(&eccentricity_vector).rem_euclid(2.0 * PI)
+        };
+        let true_anomaly = angle_from_node(&r) - argument_of_periapsis;
+
+        let semi_latus_rectum = h * h / mu;
+        let mut elements = Self {
+            semi_major_axis: 0.0,
+            eccentricity: e,
+            inclination,
+            longitude_of_ascending_node: longitude_of_ascending_node.rem_euclid(2.0 * PI),
+            argument_of_periapsis,
+            orbital_period: 0.0,
+            initial_mean_anomaly: 0.0,
+            gravitational_parameter: mu,
+            time_of_periapsis: 0.0,
+            secular_rates: None,
+        };
+
+        if e < 1.0 {
+            let a = semi_latus_rectum / (1.0 - e * e);
+            let eccentric_anomaly =
+                ((1.0 - e * e).sqrt() * true_anomaly.sin()).atan2(e + true_anomaly.cos());
+            let mean_anomaly = eccentric_anomaly - e * eccentric_anomaly.sin();
+            let mean_motion = (mu / a.powi(3)).sqrt();
+
+            elements.semi_major_axis = a;
+            elements.orbital_period = 2.0 * PI / mean_motion;
+            elements.initial_mean_anomaly = (mean_anomaly - mean_motion * time).rem_euclid(2.0 * PI);
+            elements.time_of_periapsis = time - mean_anomaly.rem_euclid(2.0 * PI) / mean_motion;
+        } else {
+            let q = semi_latus_rectum / (1.0 + e);
+            // ν se lleva a (-π, π]: antes del periapsis el tiempo es negativo.
+            let nu = (true_anomaly + PI).rem_euclid(2.0 * PI) - PI;
+            let elapsed = if e - 1.0 < PARABOLIC_TOLERANCE {
+                let d = (nu / 2.0).tan();
+                (d + d * d * d / 3.0) / (mu / (2.0 * q.powi(3))).sqrt()
+            } else {
+                let a = q / (e - 1.0);
+                let h_anomaly = 2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * (nu / 2.0).tan()).atanh();
+                (e * h_anomaly.sinh() - h_anomaly) / (mu / a.powi(3)).sqrt()
+            };
+
+            elements.semi_major_axis = q;
+            elements.time_of_periapsis = time - elapsed;
+        }
+
+        Some(elements)
+    }
+
+    /// Puntos de la parte de una órbita abierta que queda a menos de
+    /// [`OPEN_ORBIT_EXTENT`] distancias del periapsis, en orden de recorrido.
+    fn open_arc_points(&self, num_points: usize) -> Vec<DVec3> {
+        let q = self.semi_major_axis;
+        let e = self.eccentricity;
+        let semi_latus_rectum = q * (1.0 + e);
+        let max_radius = q * OPEN_ORBIT_EXTENT;
+
+        // Anomalía verdadera en la que r alcanza el límite, sin llegar a la asíntota.
+        let asymptote = if e > 1.0 { (-1.0 / e).acos() } else { PI };
+        let limit = ((semi_latus_rectum / max_radius - 1.0) / e)
+            .clamp(-1.0, 1.0)
+            .acos()
+            .min(asymptote * 0.999);
+
+        let (p, q_axis) = self.perifocal_basis();
+        let steps = num_points.max(2) - 1;
+        (0..=steps)
+            .map(|i| {
+                let true_anomaly = -limit + 2.0 * limit * i as f64 / steps as f64;
+                let radius = semi_latus_rectum / (1.0 + e * true_anomaly.cos());
+                (p * true_anomaly.cos() + q_axis * true_anomaly.sin()) * radius
+            })
+            .collect()
+    }
+
+    /// Devuelve los vectores unitarios P (hacia el periapsis) y Q (90° en el
+    /// sentido del movimiento) del plano orbital, expresados en coordenadas globales.
+    ///
+    /// Permite evaluar muchas posiciones sobre la misma órbita sin repetir
+    /// las tres rotaciones por punto.
+    ///
+    /// El espacio global es el eclíptico J2000 con Y hacia arriba:
+    /// `(x, y, z)_render = (x, z, -y)_eclíptica`. En ese mapeo las rotaciones
+    /// sobre Z de la eclíptica son rotaciones sobre Y, y el eje Y eclíptico
+    /// (dirección de Q antes de rotar) es -Z.
+    pub fn perifocal_basis(&self) -> (DVec3, DVec3) {
+        let rotate = |v: DVec3| {
+            let mut v = rotate_vec3(&v, self.argument_of_periapsis, &DVec3::y()); // ω
+            v = rotate_vec3(&v, self.inclination, &DVec3::x()); // i
+            rotate_vec3(&v, self.longitude_of_ascending_node, &DVec3::y()) // Ω
+        };
+
+        (rotate(DVec3::x()), rotate(-DVec3::z()))
     }
 
     /// Resuelve la ecuación de Kepler mediante el método de Newton-Raphson.
@@ -94,25 +637,69 @@ impl OrbitalParameters {
     ///
     /// # Retorna
     /// Anomalía excéntrica E (en radianes).
-    fn solve_kepler(&self, mean_anomaly: f32) -> f32 {
-        let mut eccentric_anomaly = mean_anomaly; // Estimación inicial
-        let e = self.eccentricity;
+    fn solve_kepler(&self, mean_anomaly: f64) -> f64 {
+        // Estimación inicial: la propia anomalía media.
+        kepler_newton(self.eccentricity, mean_anomaly, mean_anomaly, 10)
+    }
+}
 
-        // Iteración de Newton-Raphson (máximo 10 pasos).
-        for _ in 0..10 {
-            let f = eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly;
-            let f_prime = 1.0 - e * eccentric_anomaly.cos();
+/// Iteración de Newton-Raphson para la ecuación de Kepler `E - e sin(E) = M`.
+///
+/// Acepta una estimación inicial arbitraria, de modo que los llamadores que
+/// evalúan la misma órbita cuadro a cuadro pueden reutilizar la solución previa
+/// y converger en una o dos iteraciones.
+pub fn kepler_newton(eccentricity: f64, mean_anomaly: f64, initial_guess: f64, max_iterations: usize) -> f64 {
+    let mut eccentric_anomaly = initial_guess;
+    let e = eccentricity;
 
-            let delta = f / f_prime;
-            eccentric_anomaly -= delta;
+    for _ in 0..max_iterations {
+        let f = eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly;
+        let f_prime = 1.0 - e * eccentric_anomaly.cos();
 
-            if delta.abs() < 1e-6 {
-                break;
-            }
+        let delta = f / f_prime;
+        eccentric_anomaly -= delta;
+
+        if delta.abs() < 1e-12 {
+            break;
         }
+    }
+
+    eccentric_anomaly
+}
+
+/// Resuelve la ecuación de Kepler hiperbólica `e sinh(H) - H = M`.
+///
+/// El arranque `ln(2|M|/e + 1.8)` sigue el crecimiento logarítmico de H para
+/// |M| grande y no cae en la zona plana de la función cuando e ≈ 1, donde
+/// partir de M haría divergir a Newton.
+fn solve_hyperbolic_kepler(eccentricity: f64, mean_anomaly: f64) -> f64 {
+    let e = eccentricity;
+    let m = mean_anomaly.abs();
+    let mut h = (2.0 * m / e + 1.8).ln();
 
-        eccentric_anomaly
+    for _ in 0..50 {
+        let f = e * h.sinh() - h - m;
+        let f_prime = e * h.cosh() - 1.0;
+
+        let delta = f / f_prime;
+        h -= delta;
+
+        if delta.abs() < 1e-12 * h.abs().max(1.0) {
+            break;
+        }
     }
+
+    h.copysign(mean_anomaly)
+}
+
+/// Solución cerrada de la ecuación de Barker `D + D³/3 = M` para órbitas
+/// parabólicas; devuelve `D = tan(ν/2)`.
+fn solve_barker(mean_anomaly: f64) -> f64 {
+    // Se resuelve para |M| y se restituye el signo: con M muy negativo la
+    // raíz cúbica restaría dos números casi iguales.
+    let w = 1.5 * mean_anomaly.abs();
+    let y = (w + (w * w + 1.0).sqrt()).cbrt();
+    (y - 1.0 / y).copysign(mean_anomaly)
 }
 
 /// Representa un cuerpo celeste (estrella, planeta, luna o asteroide) dentro del sistema.
@@ -127,22 +714,35 @@ pub struct CelestialBody {
     pub radius: f32,
     /// Parámetros orbitales. `None` si el cuerpo está fijo (por ejemplo, el Sol).
     pub orbital_params: Option<OrbitalParameters>,
-    /// Periodo de rotación sobre su propio eje (en días simulados).
-    pub rotation_period: f32,
-    /// Vector unitario que define el eje de rotación.
-    pub rotation_axis: Vec3,
+    /// Rotación sobre su propio eje.
+    pub rotation: RotationModel,
     /// Índice del cuerpo padre en la jerarquía (por ejemplo, planeta padre de una luna).
     pub parent_index: Option<usize>,
+    /// Masa, tamaño real, albedo, temperatura y atmósfera.
+    pub physical: PhysicalProperties,
+    /// Shader de superficie.
+    pub shader: SurfaceShader,
+    /// Anillos, si los tiene.
+    pub rings: Option<Rings>,
 }
 
 impl CelestialBody {
+    /// Radio de la esfera que contiene al cuerpo y sus anillos.
+    pub fn bounding_radius(&self) -> f32 {
+        match &self.rings {
+            Some(rings) => self.radius * rings.outer_radius.max(1.0),
+            None => self.radius,
+        }
+    }
+
     /// Retorna la posición absoluta del cuerpo en el sistema de coordenadas global.
     ///
     /// Si tiene un cuerpo padre, la posición resultante será relativa al mismo.
-    pub fn get_world_position(&self, time: f32, parent_pos: Option<Vec3>) -> Vec3 {
+    /// La suma con el padre se hace en doble precisión.
+    pub fn get_world_position(&self, time: f64, parent_pos: Option<DVec3>) -> DVec3 {
         let orbital_pos = match &self.orbital_params {
             Some(params) => params.get_position(time),
-            _ => Vec3::zeros(),
+            _ => DVec3::zeros(),
         };
 
         match parent_pos {
@@ -153,19 +753,16 @@ impl CelestialBody {
 
     /// Calcula la matriz modelo del cuerpo para su representación gráfica.
     ///
-    /// Incluye transformaciones de traslación, rotación y escala.
-    pub fn get_model_matrix(&self, time: f32, world_pos: Vec3) -> Mat4 {
+    /// Incluye transformaciones de traslación, rotación y escala. `relative_pos`
+    /// es la posición del cuerpo relativa a la cámara (ver `camera::camera_relative`).
+    pub fn get_model_matrix(&self, time: f64, relative_pos: Vec3) -> Mat4 {
         let mut transform = Mat4::identity();
 
-        // Traslación a la posición espacial del cuerpo.
-        transform = nalgebra_glm::translate(&transform, &world_pos);
+        // Traslación a la posición del cuerpo respecto a la cámara.
+        transform = nalgebra_glm::translate(&transform, &relative_pos);
 
-        // Rotación axial (solo si el periodo es distinto de cero).
-        if self.rotation_period > 0.0 {
-            let rotation_angle = (time / self.rotation_period) * 2.0 * PI;
-            transform =
-                nalgebra_glm::rotate(&transform, rotation_angle, &self.rotation_axis);
-        }
+        // Inclinación del eje y giro (ver `RotationModel`).
+        transform *= self.rotation.orientation(time, self.orbital_params.as_ref());
 
         // Escala uniforme según el radio visual.
         transform =
@@ -176,18 +773,132 @@ impl CelestialBody {
 
     /// Genera un conjunto de puntos de la órbita para su visualización.
     ///
-    /// Esto permite renderizar líneas orbitales o trayectorias.
-    pub fn get_orbit_points(&self, num_points: usize) -> Vec<Vec3> {
+    /// Esto permite renderizar líneas orbitales o trayectorias. Los puntos son
+    /// relativos al padre y ya están en precisión simple, listos para el renderer.
+    /// La elipse es la osculante en `time`. Las órbitas abiertas devuelven un
+    /// arco alrededor del periapsis que no debe cerrarse al dibujarlo.
+    pub fn get_orbit_points(&self, num_points: usize, time: f64) -> Vec<Vec3> {
         match &self.orbital_params {
+            Some(params) if params.is_open() => params
+                .at_time(time)
+                .open_arc_points(num_points)
+                .iter()
+                .map(|point| point.cast::<f32>())
+                .collect(),
             Some(params) => {
+                let params = params.at_time(time);
                 let mut points = Vec::with_capacity(num_points);
                 for i in 0..num_points {
-                    let t = (i as f32 / num_points as f32) * params.orbital_period;
-                    points.push(params.get_position(t));
+                    let t = (i as f64 / num_points as f64) * params.orbital_period;
+                    points.push(params.get_position(t).cast::<f32>());
                 }
                 points
             }
             _ => Vec::new(),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Órbita inclinada alrededor de un central con `mu`; en las cerradas el
+    /// período sale de la tercera ley para que `central_gm` devuelva `mu`.
+    fn orbit(eccentricity: f64, mu: f64) -> OrbitalParameters {
+        let semi_major_axis = 1000.0;
+        OrbitalParameters {
+            semi_major_axis,
+            eccentricity,
+            inclination: 0.4,
+            longitude_of_ascending_node: 1.2,
+            argument_of_periapsis: 0.7,
+            orbital_period: if eccentricity < 1.0 {
+                2.0 * PI * (semi_major_axis.powi(3) / mu).sqrt()
+            } else {
+                0.0
+            },
+            initial_mean_anomaly: 0.3,
+            gravitational_parameter: mu,
+            time_of_periapsis: 5.0,
+            secular_rates: None,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance * expected.abs().max(1.0),
+            "{actual} != {expected}"
+        );
+    }
+
+    fn assert_round_trip(eccentricity: f64) {
+        let mu = 2.0e5;
+        let original = orbit(eccentricity, mu);
+        let time = 40.0;
+        let (position, velocity) = original.get_state(time);
+
+        let recovered = OrbitalParameters::from_state_vectors(&position, &velocity, mu, time)
+            .expect("la órbita debe estar definida");
+        assert_close(recovered.eccentricity, original.eccentricity, 1e-9);
+        assert_close(recovered.semi_major_axis, original.semi_major_axis, 1e-9);
+        assert_close(recovered.inclination, original.inclination, 1e-9);
+        assert_close(recovered.longitude_of_ascending_node, original.longitude_of_ascending_node, 1e-9);
+        assert_close(recovered.argument_of_periapsis, original.argument_of_periapsis, 1e-9);
+        assert_eq!(recovered.is_open(), original.is_open());
+
+        // Los elementos recuperados reproducen el estado en otro instante.
+        let later = time + 25.0;
+        let (expected_position, expected_velocity) = original.get_state(later);
+        let (actual_position, actual_velocity) = recovered.get_state(later);
+        assert!((actual_position - expected_position).magnitude() < 1e-6 * expected_position.magnitude());
+        assert!((actual_velocity - expected_velocity).magnitude() < 1e-6 * expected_velocity.magnitude());
+    }
+
+    #[test]
+    fn hyperbolic_kepler_solution_satisfies_equation() {
+        for eccentricity in [1.0001, 1.5, 4.0] {
+            for mean_anomaly in [-50.0, -1.0, 0.0, 0.2, 3.0, 1e4] {
+                let h = solve_hyperbolic_kepler(eccentricity, mean_anomaly);
+                assert_close(eccentricity * h.sinh() - h, mean_anomaly, 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn barker_solution_satisfies_equation() {
+        for mean_anomaly in [-1e3, -2.0, 0.0, 0.5, 40.0] {
+            let d = solve_barker(mean_anomaly);
+            assert_close(d + d.powi(3) / 3.0, mean_anomaly, 1e-12);
+        }
+    }
+
+    #[test]
+    fn open_orbits_pass_periapsis_at_time_of_periapsis() {
+        for eccentricity in [1.0, 2.5] {
+            let params = orbit(eccentricity, 2.0e5);
+            let (position, velocity) = params.get_state(params.time_of_periapsis);
+            assert_close(position.magnitude(), params.periapsis_distance(), 1e-12);
+            assert!(position.dot(&velocity).abs() < 1e-9 * position.magnitude() * velocity.magnitude());
+
+            // Energía `v²/2 - μ/r` igual en cualquier punto del arco.
+            let (position, velocity) = params.get_state(params.time_of_periapsis + 80.0);
+            let energy = velocity.magnitude_squared() / 2.0 - params.central_gm() / position.magnitude();
+            assert!((energy - params.specific_energy()).abs() < 1e-9 * params.central_gm() / position.magnitude());
+        }
+    }
+
+    #[test]
+    fn elliptic_elements_survive_state_round_trip() {
+        assert_round_trip(0.3);
+    }
+
+    #[test]
+    fn parabolic_elements_survive_state_round_trip() {
+        assert_round_trip(1.0);
+    }
+
+    #[test]
+    fn hyperbolic_elements_survive_state_round_trip() {
+        assert_round_trip(2.5);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::epoch::CalendarDate;
+
+/// Opciones de línea de comandos.
+#[derive(Default)]
+pub struct CliOptions {
+    /// Escenario TOML a cargar en lugar del sistema solar incluido.
+    pub scenario: Option<String>,
+    /// Si está presente, se guarda el escenario activo en este archivo y se sale.
+    pub save_scenario: Option<String>,
+    /// Cuerpo seleccionado y enfocado al arrancar.
+    pub focus: Option<String>,
+    /// Si está presente, se genera el diagrama porkchop entre estos dos
+    /// cuerpos (origen y destino) y se sale sin abrir la ventana.
+    pub porkchop: Option<(String, String)>,
+    /// Fecha desde la que se busca la ventana del diagrama (días desde J2000);
+    /// por defecto, hoy.
+    pub porkchop_start: Option<f64>,
+    /// Archivo PNG del diagrama.
+    pub porkchop_output: Option<String>,
+    /// Semilla de los generadores aleatorios; tiene prioridad sobre la del
+    /// escenario.
+    pub seed: Option<u64>,
+    /// Mostrar la ayuda y salir.
+    pub help: bool,
+}
+
+impl CliOptions {
+    /// Interpreta los argumentos (sin el nombre del programa).
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = CliOptions::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--scenario" => options.scenario = Some(expect_value(&mut args, &arg)?),
+                "--save-scenario" => options.save_scenario = Some(expect_value(&mut args, &arg)?),
+                "--focus" => options.focus = Some(expect_value(&mut args, &arg)?),
+                "--porkchop" => {
+                    let origin = expect_value(&mut args, &arg)?;
+                    let target = expect_value(&mut args, &arg)?;
+                    options.porkchop = Some((origin, target));
+                }
+                "--porkchop-start" => {
+                    let date = CalendarDate::parse(&expect_value(&mut args, &arg)?)?;
+                    options.porkchop_start = Some(date.to_simulation_time());
+                }
+                "--porkchop-output" => options.porkchop_output = Some(expect_value(&mut args, &arg)?),
+                "--seed" => {
+                    let value = expect_value(&mut args, &arg)?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("Semilla no válida: {}", value))?;
+                    options.seed = Some(seed);
+                }
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("Argumento desconocido: {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+
+    pub fn usage() -> &'static str {
+        "Uso: proyecto3 [opciones]\n\
+         \n\
+         Opciones:\n  \
+           --scenario <archivo.toml>       Carga un escenario en lugar del sistema solar\n  \
+           --save-scenario <archivo.toml>  Guarda el escenario activo y termina\n  \
+           --focus <nombre>                Selecciona y enfoca un cuerpo al arrancar\n  \
+           --porkchop <origen> <destino>   Genera el diagrama porkchop de la transferencia y termina\n  \
+           --porkchop-start <AAAA-MM-DD>   Busca la ventana del diagrama desde esta fecha (por defecto, hoy)\n  \
+           --porkchop-output <archivo.png> Imagen del diagrama (por defecto, porkchop.png)\n  \
+           --seed <n>                      Semilla del fondo estelar y los asteroides\n  \
+           -h, --help                      Muestra esta ayuda"
+    }
+}
+
+fn expect_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("Falta el valor de {}", flag))
+}
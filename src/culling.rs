@@ -0,0 +1,163 @@
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+
+/// Plano en forma `normal · p + distance = 0`, con la normal apuntando hacia
+/// el interior del volumen.
+#[derive(Debug, Clone, Copy)]
+struct Plane {
+    normal: Vec3,
+    distance: f32,
+}
+
+impl Plane {
+    /// Construye el plano a partir de una combinación de filas de la matriz
+    /// y lo normaliza para que `signed_distance` devuelva unidades de mundo.
+    fn from_row(row: Vec4) -> Self {
+        let normal = Vec3::new(row.x, row.y, row.z);
+        let length = normal.magnitude();
+
+        // Con un plano cercano muy pequeño frente al lejano, en f32 el plano
+        // lejano degenera (normal nula). Se trata como un plano que no recorta.
+        if length <= f32::EPSILON {
+            return Plane {
+                normal: Vec3::zeros(),
+                distance: f32::INFINITY,
+            };
+        }
+
+        Plane {
+            normal: normal / length,
+            distance: row.w / length,
+        }
+    }
+
+    fn signed_distance(&self, point: &Vec3) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+/// Frustum de vista definido por sus seis planos.
+///
+/// Los planos se extraen directamente de la matriz vista-proyección
+/// (método de Gribb-Hartmann), así que la prueba es exacta para la cámara
+/// y la proyección usadas en el cuadro.
+#[derive(Debug, Clone, Copy)]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extrae los planos izquierdo, derecho, inferior, superior, cercano y
+    /// lejano de una matriz vista-proyección con NDC z en [-1, 1].
+    pub fn from_matrix(view_projection: &Mat4) -> Self {
+        let row = |i: usize| {
+            Vec4::new(
+                view_projection[(i, 0)],
+                view_projection[(i, 1)],
+                view_projection[(i, 2)],
+                view_projection[(i, 3)],
+            )
+        };
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+
+        Frustum {
+            planes: [
+                Plane::from_row(r3 + r0),
+                Plane::from_row(r3 - r0),
+                Plane::from_row(r3 + r1),
+                Plane::from_row(r3 - r1),
+                Plane::from_row(r3 + r2),
+                Plane::from_row(r3 - r2),
+            ],
+        }
+    }
+
+    /// Devuelve `false` solo si la esfera queda completamente fuera de algún plano.
+    pub fn intersects_sphere(&self, center: &Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(center) >= -radius)
+    }
+}
+
+/// Esfera opaca en espacio de vista que puede ocultar a otras.
+struct SphereOccluder {
+    index: usize,
+    direction: Vec3,
+    distance: f32,
+    angular_radius: f32,
+}
+
+/// Radio angular mínimo (radianes) para que un cuerpo se considere oclusor.
+/// Los cuerpos más pequeños en pantalla casi nunca tapan nada y solo añaden coste.
+const MIN_OCCLUDER_ANGLE: f32 = 0.005;
+
+/// Oclusión analítica por esferas.
+///
+/// Los cuerpos grandes (planetas, estrellas) se registran como oclusores
+/// esféricos. Un objeto se descarta cuando su cono visual cabe entero dentro
+/// del cono de un oclusor y todo él está más lejos que el centro de ese
+/// oclusor: cualquier rayo dentro del cono choca con la esfera antes de esa
+/// distancia, así que la prueba es conservadora.
+pub struct OcclusionCuller {
+    view_matrix: Mat4,
+    occluders: Vec<SphereOccluder>,
+}
+
+impl OcclusionCuller {
+    /// Crea un conjunto de oclusores vacío para la vista del cuadro actual.
+    pub fn new(view_matrix: &Mat4) -> Self {
+        Self {
+            view_matrix: *view_matrix,
+            occluders: Vec::new(),
+        }
+    }
+
+    /// Registra la esfera del cuerpo `index` como oclusor.
+    pub fn add_occluder(&mut self, index: usize, center: &Vec3, radius: f32) {
+        let view_center = self.to_view(center);
+        let distance = view_center.magnitude();
+
+        // Con la cámara dentro (o rozando) la esfera no hay cono definido.
+        if distance <= radius * 1.001 {
+            return;
+        }
+
+        let angular_radius = (radius / distance).asin();
+        if angular_radius < MIN_OCCLUDER_ANGLE {
+            return;
+        }
+
+        self.occluders.push(SphereOccluder {
+            index,
+            direction: view_center / distance,
+            distance,
+            angular_radius,
+        });
+    }
+
+    /// Indica si la esfera del cuerpo `index` queda oculta por otro oclusor.
+    pub fn is_occluded(&self, index: usize, center: &Vec3, radius: f32) -> bool {
+        let view_center = self.to_view(center);
+        let distance = view_center.magnitude();
+        if distance <= radius {
+            return false;
+        }
+
+        let direction = view_center / distance;
+        let angular_radius = (radius / distance).asin();
+
+        self.occluders.iter().any(|occluder| {
+            if occluder.index == index || distance - radius < occluder.distance {
+                return false;
+            }
+
+            let separation = direction.dot(&occluder.direction).clamp(-1.0, 1.0).acos();
+            separation + angular_radius <= occluder.angular_radius
+        })
+    }
+
+    fn to_view(&self, point: &Vec3) -> Vec3 {
+        let view = self.view_matrix * Vec4::new(point.x, point.y, point.z, 1.0);
+        Vec3::new(view.x, view.y, view.z)
+    }
+}
@@ -0,0 +1,205 @@
+use crate::framebuffer::Framebuffer;
+use nalgebra_glm::{DVec3, Mat4};
+
+/// Escala mínima de la resolución interna respecto a la de salida.
+const MIN_SCALE: f32 = 0.5;
+/// Escala máxima (resolución nativa).
+const MAX_SCALE: f32 = 1.0;
+/// Máximo cambio de escala por ajuste, para evitar saltos visibles.
+const MAX_SCALE_STEP: f32 = 0.05;
+/// Cuadros que se espera tras un cambio de tamaño antes de volver a ajustar,
+/// para que el promedio refleje ya el coste de la nueva resolución.
+const RESIZE_COOLDOWN_FRAMES: u32 = 8;
+/// Factor del promedio exponencial del tiempo de render.
+const SMOOTHING: f32 = 0.15;
+/// Granularidad (en píxeles) del ancho interno; evita reasignar buffers por cambios mínimos.
+const WIDTH_GRANULARITY: usize = 16;
+
+/// Controlador de resolución dinámica.
+///
+/// Mide cuánto tarda el render por software en cada cuadro y ajusta la
+/// resolución interna del framebuffer para acercarse a un tiempo objetivo.
+/// Como el coste dominante es el sombreado por píxel, el ajuste usa la raíz
+/// cuadrada de la razón objetivo / medido (el área escala con `scale²`).
+pub struct DynamicResolution {
+    /// Si está desactivado se renderiza siempre a resolución nativa.
+    pub enabled: bool,
+    /// Tiempo de render objetivo por cuadro, en milisegundos.
+    pub target_ms: f32,
+    output_width: usize,
+    output_height: usize,
+    scale: f32,
+    smoothed_ms: f32,
+    cooldown: u32,
+}
+
+impl DynamicResolution {
+    /// Crea un controlador para una salida de `output_width` × `output_height`.
+    pub fn new(output_width: usize, output_height: usize, target_ms: f32) -> Self {
+        Self {
+            enabled: true,
+            target_ms,
+            output_width,
+            output_height,
+            scale: MAX_SCALE,
+            smoothed_ms: target_ms,
+            cooldown: 0,
+        }
+    }
+
+    /// Escala actual de la resolución interna (1.0 = nativa).
+    pub fn scale(&self) -> f32 {
+        if self.enabled { self.scale } else { MAX_SCALE }
+    }
+
+    /// Registra el tiempo de render del último cuadro y recalcula la escala.
+    pub fn update(&mut self, render_ms: f32) {
+        self.smoothed_ms += (render_ms - self.smoothed_ms) * SMOOTHING;
+
+        if !self.enabled {
+            return;
+        }
+        if self.cooldown > 0 {
+            self.cooldown -= 1;
+            return;
+        }
+
+        // Banda muerta para no oscilar alrededor del objetivo.
+        let ratio = self.target_ms / self.smoothed_ms.max(0.001);
+        if (0.9..=1.05).contains(&ratio) {
+            return;
+        }
+
+        let desired = self.scale * ratio.sqrt();
+        let step = (desired - self.scale).clamp(-MAX_SCALE_STEP, MAX_SCALE_STEP);
+        let new_scale = (self.scale + step).clamp(MIN_SCALE, MAX_SCALE);
+
+        if self.internal_size_for(new_scale) != self.internal_size_for(self.scale) {
+            self.cooldown = RESIZE_COOLDOWN_FRAMES;
+        }
+        self.scale = new_scale;
+    }
+
+    /// Resolución interna a la que se debe renderizar el cuadro actual.
+    pub fn internal_size(&self) -> (usize, usize) {
+        self.internal_size_for(self.scale())
+    }
+
+    fn internal_size_for(&self, scale: f32) -> (usize, usize) {
+        let width = ((self.output_width as f32 * scale) as usize / WIDTH_GRANULARITY
+            * WIDTH_GRANULARITY)
+            .clamp(WIDTH_GRANULARITY, self.output_width);
+        let height = (width * self.output_height / self.output_width).max(1);
+        (width, height)
+    }
+}
+
+/// Acumulación temporal de cuadros con jitter sub-píxel.
+///
+/// Mientras la cámara está quieta, cada cuadro se renderiza con la proyección
+/// desplazada una fracción de píxel (secuencia de Halton 2,3) y se mezcla con
+/// el historial a resolución de salida. Así se recupera detalle perdido por la
+/// resolución interna reducida y se suavizan los bordes. En cuanto la cámara
+/// se mueve o avanza la simulación el historial se descarta para no dejar
+/// estelas, así que solo se acumula en pausa; la interfaz solo ofrece
+/// activarla entonces y la muestra en espera mientras corre el tiempo.
+pub struct TemporalAccumulator {
+    /// Activa o desactiva la acumulación.
+    pub enabled: bool,
+    history: Vec<f32>,
+    frames: u32,
+    last_view: Option<(Mat4, DVec3, f64)>,
+    still: bool,
+}
+
+/// Número de muestras distintas de la secuencia de jitter.
+const JITTER_SAMPLES: u32 = 8;
+/// Peso mínimo del cuadro nuevo; limita cuánto "recuerda" el historial.
+const MIN_BLEND: f32 = 0.1;
+
+impl TemporalAccumulator {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            history: Vec::new(),
+            frames: 0,
+            last_view: None,
+            still: false,
+        }
+    }
+
+    /// Compara orientación y posición de la cámara y el tiempo de simulación
+    /// con los del cuadro anterior para decidir si la escena está quieta: con
+    /// los cuerpos orbitando y girando, mezclar cuadros dejaría estelas. Debe
+    /// llamarse una vez por cuadro antes de renderizar.
+    pub fn begin_frame(&mut self, view_matrix: &Mat4, camera_position: &DVec3, simulation_time: f64) {
+        let current = (*view_matrix, *camera_position, simulation_time);
+        self.still = self.enabled && self.last_view == Some(current);
+        self.last_view = Some(current);
+        if !self.still {
+            self.frames = 0;
+        }
+    }
+
+    /// Indica si en este cuadro se está acumulando.
+    pub fn is_accumulating(&self) -> bool {
+        self.still
+    }
+
+    /// Aplica el desplazamiento sub-píxel del cuadro actual a una proyección
+    /// para un framebuffer de `width` × `height`.
+    pub fn jitter_projection(&self, projection: &Mat4, width: usize, height: usize) -> Mat4 {
+        if !self.still {
+            return *projection;
+        }
+
+        let index = self.frames % JITTER_SAMPLES + 1;
+        let jitter_x = (halton(index, 2) - 0.5) * 2.0 / width as f32;
+        let jitter_y = (halton(index, 3) - 0.5) * 2.0 / height as f32;
+
+        // Sumar en la tercera columna desplaza x/w e y/w de forma constante en NDC.
+        let mut jittered = *projection;
+        jittered[(0, 2)] += jitter_x;
+        jittered[(1, 2)] += jitter_y;
+        jittered
+    }
+
+    /// Mezcla el cuadro ya escalado con el historial y escribe el resultado en `output`.
+    pub fn resolve(&mut self, output: &mut Framebuffer) {
+        let pixel_count = output.width * output.height;
+        if self.history.len() != pixel_count * 3 {
+            self.history = vec![0.0; pixel_count * 3];
+            self.frames = 0;
+        }
+
+        if !self.still {
+            return;
+        }
+
+        let blend = (1.0 / (self.frames + 1) as f32).max(MIN_BLEND);
+        let bytes = output.buffer.as_mut_slice();
+
+        for i in 0..pixel_count {
+            for c in 0..3 {
+                let current = bytes[i * 4 + c] as f32;
+                let accumulated = &mut self.history[i * 3 + c];
+                *accumulated += (current - *accumulated) * blend;
+                bytes[i * 4 + c] = accumulated.round() as u8;
+            }
+        }
+
+        self.frames += 1;
+    }
+}
+
+/// Elemento `index` de la secuencia de Halton en la base dada, en [0, 1).
+fn halton(mut index: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut fraction = 1.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
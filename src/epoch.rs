@@ -0,0 +1,287 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Fecha juliana de la época J2000.0 (2000-01-01 12:00).
+pub const J2000_JD: f64 = 2451545.0;
+/// Fecha juliana del origen de tiempo Unix (1970-01-01 00:00 UTC).
+const UNIX_EPOCH_JD: f64 = 2440587.5;
+/// Primer día del calendario gregoriano (1582-10-15) como día juliano entero.
+const GREGORIAN_START_JDN: f64 = 2299161.0;
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Fecha juliana correspondiente a un tiempo de simulación (días desde J2000).
+pub fn julian_date(simulation_time: f64) -> f64 {
+    J2000_JD + simulation_time
+}
+
+/// Tiempo de simulación correspondiente a una fecha juliana.
+pub fn simulation_time_from_julian_date(jd: f64) -> f64 {
+    jd - J2000_JD
+}
+
+/// Tiempo de simulación del instante actual según el reloj del sistema.
+pub fn now() -> f64 {
+    let unix_seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+
+    simulation_time_from_julian_date(UNIX_EPOCH_JD + unix_seconds / SECONDS_PER_DAY)
+}
+
+/// Fecha y hora de calendario.
+///
+/// Usa el calendario gregoriano desde 1582-10-15 y el juliano antes. La
+/// simulación cuenta en TDB y la fecha se muestra como UTC sin aplicar
+/// ΔT (~1 min en la actualidad), despreciable a la escala del simulador.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalendarDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl CalendarDate {
+    /// Convierte una fecha juliana a calendario (Meeus, cap. 7), redondeando
+    /// al segundo.
+    pub fn from_julian_date(jd: f64) -> Self {
+        let shifted = jd + 0.5;
+        let mut day_number = shifted.floor();
+        let mut seconds = ((shifted - day_number) * SECONDS_PER_DAY).round() as u32;
+        if seconds >= SECONDS_PER_DAY as u32 {
+            day_number += 1.0;
+            seconds = 0;
+        }
+
+        let a = if day_number < GREGORIAN_START_JDN {
+            day_number
+        } else {
+            let alpha = ((day_number - 1867216.25) / 36524.25).floor();
+            day_number + 1.0 + alpha - (alpha / 4.0).floor()
+        };
+        let b = a + 1524.0;
+        let c = ((b - 122.1) / 365.25).floor();
+        let d = (365.25 * c).floor();
+        let e = ((b - d) / 30.6001).floor();
+
+        let day = (b - d - (30.6001 * e).floor()) as u32;
+        let month = (if e < 14.0 { e - 1.0 } else { e - 13.0 }) as u32;
+        let year = (if month > 2 { c - 4716.0 } else { c - 4715.0 }) as i32;
+
+        Self {
+            year,
+            month,
+            day,
+            hour: seconds / 3600,
+            minute: seconds / 60 % 60,
+            second: seconds % 60,
+        }
+    }
+
+    /// Fecha juliana de esta fecha (Meeus, cap. 7).
+    pub fn to_julian_date(self) -> f64 {
+        let (mut year, mut month) = (self.year as f64, self.month as f64);
+        if month <= 2.0 {
+            year -= 1.0;
+            month += 12.0;
+        }
+
+        let is_gregorian = (self.year, self.month, self.day) >= (1582, 10, 15);
+        let b = if is_gregorian {
+            let a = (year / 100.0).floor();
+            2.0 - a + (a / 4.0).floor()
+        } else {
+            0.0
+        };
+
+        let day_fraction =
+            (self.hour * 3600 + self.minute * 60 + self.second) as f64 / SECONDS_PER_DAY;
+
+        (365.25 * (year + 4716.0)).floor() + (30.6001 * (month + 1.0)).floor()
+            + self.day as f64
+            + day_fraction
+            + b
+            - 1524.5
+    }
+
+    pub fn from_simulation_time(simulation_time: f64) -> Self {
+        Self::from_julian_date(julian_date(simulation_time))
+    }
+
+    pub fn to_simulation_time(self) -> f64 {
+        simulation_time_from_julian_date(self.to_julian_date())
+    }
+
+    /// Interpreta una fecha `AAAA-MM-DD`, opcionalmente seguida de `HH:MM` o
+    /// `HH:MM:SS` (separada por espacio o `T`).
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (date_part, time_part) = match text.split_once([' ', 'T']) {
+            Some((date, time)) => (date, Some(time.trim())),
+            None => (text, None),
+        };
+
+        let date_fields: Vec<&str> = date_part.split('-').collect();
+        if date_fields.len() != 3 {
+            return Err(format!("Fecha inválida '{}': se esperaba AAAA-MM-DD", date_part));
+        }
+        let year: i32 = parse_field(date_fields[0], "año")?;
+        let month: u32 = parse_field(date_fields[1], "mes")?;
+        let day: u32 = parse_field(date_fields[2], "día")?;
+
+        if !(1..=12).contains(&month) {
+            return Err(format!("Mes fuera de rango: {}", month));
+        }
+        if day == 0 || day > days_in_month(year, month) {
+            return Err(format!("Día fuera de rango: {}", day));
+        }
+
+        let (hour, minute, second) = match time_part {
+            Some(time) if !time.is_empty() => {
+                let time_fields: Vec<&str> = time.split(':').collect();
+                if !(2..=3).contains(&time_fields.len()) {
+                    return Err(format!("Hora inválida '{}': se esperaba HH:MM[:SS]", time));
+                }
+                let hour: u32 = parse_field(time_fields[0], "hora")?;
+                let minute: u32 = parse_field(time_fields[1], "minuto")?;
+                let second: u32 = match time_fields.get(2) {
+                    Some(field) => parse_field(field, "segundo")?,
+                    None => 0,
+                };
+                if hour > 23 || minute > 59 || second > 59 {
+                    return Err(format!("Hora fuera de rango: {}", time));
+                }
+                (hour, minute, second)
+            }
+            _ => (0, 0, 0),
+        };
+
+        Ok(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+fn parse_field<T: std::str::FromStr>(field: &str, name: &str) -> Result<T, String> {
+    field
+        .trim()
+        .parse()
+        .map_err(|_| format!("Valor de {} inválido: '{}'", name, field))
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 => {
+            let leap = if year < 1582 {
+                year % 4 == 0
+            } else {
+                (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+            };
+            if leap { 29 } else { 28 }
+        }
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32, hour: u32) -> CalendarDate {
+        CalendarDate {
+            year,
+            month,
+            day,
+            hour,
+            minute: 0,
+            second: 0,
+        }
+    }
+
+    #[test]
+    fn j2000_is_julian_date_2451545() {
+        let j2000 = date(2000, 1, 1, 12);
+        assert_eq!(j2000.to_julian_date(), J2000_JD);
+        assert_eq!(j2000.to_simulation_time(), 0.0);
+        assert_eq!(CalendarDate::from_simulation_time(0.0), j2000);
+    }
+
+    #[test]
+    fn julian_calendar_applies_before_the_gregorian_reform() {
+        // Meeus, ej. 7.b: 333-01-27 12:00 (juliano) = JD 1842713.0.
+        assert_eq!(date(333, 1, 27, 12).to_julian_date(), 1842713.0);
+        assert_eq!(CalendarDate::from_julian_date(1842713.0), date(333, 1, 27, 12));
+
+        // El 4 de octubre de 1582 (juliano) va seguido del 15 (gregoriano).
+        let last_julian = date(1582, 10, 4, 0).to_julian_date();
+        assert_eq!(date(1582, 10, 15, 0).to_julian_date(), last_julian + 1.0);
+    }
+
+    #[test]
+    fn leap_days_round_trip() {
+        for leap_day in [date(2024, 2, 29, 6), date(1600, 2, 29, 0), date(1500, 2, 29, 18)] {
+            assert_eq!(CalendarDate::from_julian_date(leap_day.to_julian_date()), leap_day);
+        }
+        assert_eq!(
+            date(2024, 3, 1, 0).to_julian_date() - date(2024, 2, 28, 0).to_julian_date(),
+            2.0
+        );
+    }
+
+    #[test]
+    fn parse_accepts_date_with_optional_time() {
+        assert_eq!(CalendarDate::parse("2000-01-01"), Ok(date(2000, 1, 1, 0)));
+        assert_eq!(CalendarDate::parse(" 2024-02-29T06:00 "), Ok(date(2024, 2, 29, 6)));
+        assert_eq!(
+            CalendarDate::parse("1969-07-20 20:17:40"),
+            Ok(CalendarDate {
+                year: 1969,
+                month: 7,
+                day: 20,
+                hour: 20,
+                minute: 17,
+                second: 40,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed_dates() {
+        for text in [
+            "",
+            "2000-01",
+            "2000/01/01",
+            "año-01-01",
+            "2000-13-01",
+            "2000-00-10",
+            "2023-02-29",
+            "1900-02-29",
+            "2000-04-31",
+            "2000-01-01 12",
+            "2000-01-01 24:00",
+            "2000-01-01 12:60",
+            "2000-01-01 12:00:00:00",
+        ] {
+            assert!(CalendarDate::parse(text).is_err(), "'{text}' no debería aceptarse");
+        }
+    }
+}
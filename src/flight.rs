@@ -0,0 +1,426 @@
+use crate::celestial_body::{CelestialBody, OrbitalParameters};
+use crate::gravity::GravityParameters;
+use crate::solar_system::AU;
+use nalgebra_glm::DVec3;
+use std::f64::consts::PI;
+
+/// Metros por unidad de simulación.
+pub const METERS_PER_UNIT: f64 = 1.495_978_707e11 / AU;
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Empuje del motor por defecto (m/s²).
+pub const DEFAULT_THRUST: f64 = 1.0;
+
+/// Cada subpaso de RK4 avanza esta fracción del tiempo característico de la
+/// órbita (`√(r³/μ)` o `r/v`, el menor): unos 1250 pasos por vuelta.
+const STEP_FRACTION: f64 = 0.005;
+/// Límite de subpasos por cuadro; por encima el paso crece.
+const MAX_SUBSTEPS: usize = 2000;
+/// Saltos de tiempo mayores que este (días) no se integran: la nave conserva
+/// su estado relativo al cuerpo de referencia.
+const RESEED_THRESHOLD: f64 = 30.0;
+/// Distancia mínima (en radios del cuerpo) a la que se coloca la nave al
+/// pasar a vuelo newtoniano, por ejemplo tras viajar al centro de un planeta.
+const PARKING_RADII: f64 = 3.0;
+/// Exponente de Laplace de la esfera de influencia: `r = a·(m/M)^(2/5)`.
+const SOI_EXPONENT: f64 = 0.4;
+/// Alcance de la predicción de trayectorias abiertas en el marco raíz, en
+/// múltiplos de la distancia actual.
+const PREDICTION_EXTENT: f64 = 10.0;
+
+/// Gravedad por cónicas enlazadas: en cada región solo atrae el cuerpo cuya
+/// esfera de influencia (SOI) la contiene.
+///
+/// Son marcos los cuerpos con órbita y masa; el resto pertenece al marco de
+/// su padre, y `None` es el marco raíz, centrado en el origen y dominado por
+/// el cuerpo sin órbita más masivo (el Sol). Las masas son las de
+/// [`GravityParameters`] y los marcos se mueven sobre sus raíles de Kepler.
+pub struct GravityField {
+    /// Marco al que pertenece cada cuerpo: él mismo si es un marco.
+    frames: Vec<Option<usize>>,
+    /// Marco que contiene a cada cuerpo, sin contarse a sí mismo.
+    parent_frames: Vec<Option<usize>>,
+    /// GM con el que atrae cada marco dentro de su esfera.
+    gm: Vec<f64>,
+    /// Radio de la esfera de influencia de cada marco (0 si no lo es).
+    soi: Vec<f64>,
+    radii: Vec<f64>,
+    /// Cuerpo central del marco raíz.
+    root: Option<usize>,
+}
+
+impl GravityField {
+    pub fn new(bodies: &[CelestialBody]) -> Self {
+        let gravity = GravityParameters::new(bodies);
+        let count = bodies.len();
+
+        let is_frame =
+            |id: usize| bodies[id].orbital_params.is_some() && gravity.attraction(id) > 0.0;
+        let frame_of = |id: usize| {
+            let mut current = Some(id);
+            while let Some(id) = current {
+                if is_frame(id) {
+                    return Some(id);
+                }
+                current = bodies[id].parent_index;
+            }
+            None
+        };
+        let frames: Vec<Option<usize>> = (0..count).map(frame_of).collect();
+        let parent_frames: Vec<Option<usize>> = bodies
+            .iter()
+            .map(|body| body.parent_index.and_then(|parent| frames[parent]))
+            .collect();
+
+        let root = (0..count)
+            .filter(|&id| frames[id].is_none())
+            .max_by(|&a, &b| gravity.attraction(a).total_cmp(&gravity.attraction(b)));
+
+        let soi = (0..count)
+            .map(|id| {
+                let Some(params) = bodies[id].orbital_params.as_ref().filter(|_| is_frame(id)) else {
+                    return 0.0;
+                };
+
+                // Laplace con la proporción de masas respecto al central...
+                let central_gm = gravity.orbit_gm[id];
+                let laplace = if central_gm > 0.0 {
+                    params.semi_major_axis * (gravity.gm[id] / central_gm).powf(SOI_EXPONENT)
+                } else {
+                    0.0
+                };
+                // ...pero con las lunas (de órbitas exageradas) siempre dentro
+                // y sin alcanzar nunca al cuerpo central.
+                let satellites = (0..count)
+                    .filter(|&j| parent_frames[j] == Some(id))
+                    .filter_map(|j| bodies[j].orbital_params.as_ref())
+                    .map(|p| p.apoapsis_distance().unwrap_or_else(|| p.periapsis_distance()))
+                    .fold(0.0, f64::max);
+
+                laplace
+                    .max(bodies[id].radius as f64 * 4.0)
+                    .max(satellites * 1.1)
+                    .min(params.periapsis_distance() * 0.5)
+            })
+            .collect();
+
+        Self {
+            frames,
+            parent_frames,
+            gm: (0..count).map(|id| gravity.attraction(id)).collect(),
+            soi,
+            radii: bodies.iter().map(|b| b.radius as f64).collect(),
+            root,
+        }
+    }
+
+    /// GM del cuerpo que domina el marco.
+    pub fn gm(&self, frame: Option<usize>) -> f64 {
+        match frame.or(self.root) {
+            Some(id) => self.gm[id],
+            None => 0.0,
+        }
+    }
+
+    /// Radio de la esfera de influencia del marco (infinito para el raíz).
+    pub fn soi_radius(&self, frame: Option<usize>) -> f64 {
+        match frame {
+            Some(id) => self.soi[id],
+            None => f64::INFINITY,
+        }
+    }
+
+    /// Radio visual del cuerpo que domina el marco.
+    pub fn surface_radius(&self, frame: Option<usize>) -> f64 {
+        match frame.or(self.root) {
+            Some(id) => self.radii[id],
+            None => 0.0,
+        }
+    }
+
+    /// Cuerpo que domina el marco: él mismo o, en el raíz, el central.
+    pub fn body(&self, frame: Option<usize>) -> Option<usize> {
+        frame.or(self.root)
+    }
+
+    /// Posición absoluta del origen del marco.
+    pub fn origin(&self, frame: Option<usize>, world_positions: &[DVec3]) -> DVec3 {
+        frame.map(|id| world_positions[id]).unwrap_or_else(DVec3::zeros)
+    }
+
+    /// Marco más interno cuya esfera contiene `world_position`.
+    pub fn locate(&self, world_position: &DVec3, world_positions: &[DVec3]) -> Option<usize> {
+        let mut frame = None;
+        while let Some(child) = self
+            .child_frames(frame)
+            .find(|&id| (world_position - world_positions[id]).magnitude() < self.soi[id])
+        {
+            frame = Some(child);
+        }
+        frame
+    }
+
+    fn child_frames(&self, frame: Option<usize>) -> impl Iterator<Item = usize> + '_ {
+        (0..self.frames.len())
+            .filter(move |&id| self.frames[id] == Some(id) && self.parent_frames[id] == frame)
+    }
+
+    /// Posición y velocidad del marco `frame` respecto a su marco padre.
+    fn relative_state(&self, bodies: &[CelestialBody], frame: usize, time: f64) -> (DVec3, DVec3) {
+        let target = self.parent_frames[frame];
+        let (mut position, mut velocity) = (DVec3::zeros(), DVec3::zeros());
+        let mut current = Some(frame);
+        while let Some(id) = current.filter(|&id| Some(id) != target) {
+            if let Some(params) = &bodies[id].orbital_params {
+                let (p, v) = params.get_state(time);
+                position += p;
+                velocity += v;
+            }
+            current = bodies[id].parent_index;
+        }
+        (position, velocity)
+    }
+}
+
+/// Nave en vuelo newtoniano: empuje en m/s² y gravedad del cuerpo dominante.
+///
+/// El estado es relativo al cuerpo de referencia y se integra con RK4 al
+/// ritmo del tiempo de simulación (en pausa la nave no se mueve). Al cruzar
+/// el borde de una esfera de influencia el estado se traslada al marco nuevo
+/// con la velocidad de Kepler del cuerpo; en modo N cuerpos los marcos se
+/// dibujan en las posiciones integradas, pero las transiciones usan Kepler.
+pub struct NewtonianShip {
+    /// Marco en el que está la nave (ver [`GravityField`]).
+    pub reference: Option<usize>,
+    /// Posición relativa a la referencia (unidades).
+    pub position: DVec3,
+    /// Velocidad relativa a la referencia (unidades/día).
+    pub velocity: DVec3,
+    /// Empuje máximo del motor (m/s²).
+    pub thrust: f64,
+    /// Posada sobre la superficie del cuerpo de referencia.
+    pub landed: bool,
+    time: f64,
+}
+
+impl NewtonianShip {
+    /// Coloca la nave en `world_position` en órbita circular prograda
+    /// alrededor del cuerpo dominante.
+    pub fn new(world_position: DVec3, time: f64, field: &GravityField, world_positions: &[DVec3]) -> Self {
+        let reference = field.locate(&world_position, world_positions);
+        let mut position = world_position - field.origin(reference, world_positions);
+
+        let parking = field.surface_radius(reference) * PARKING_RADII;
+        if position.magnitude() < parking {
+            let direction = if position.magnitude() > 1e-9 { position.normalize() } else { DVec3::x() };
+            position = direction * parking;
+        }
+
+        Self {
+            reference,
+            velocity: circular_velocity(&position, field.gm(reference)),
+            position,
+            thrust: DEFAULT_THRUST,
+            landed: false,
+            time,
+        }
+    }
+
+    /// Posición absoluta, sobre las posiciones de los cuerpos de este cuadro.
+    pub fn world_position(&self, field: &GravityField, world_positions: &[DVec3]) -> DVec3 {
+        field.origin(self.reference, world_positions) + self.position
+    }
+
+    /// Integra hasta `time` con el empuje `thrust` (m/s², ejes globales).
+    pub fn update(&mut self, thrust: DVec3, time: f64, field: &GravityField, bodies: &[CelestialBody]) {
+        let elapsed = time - self.time;
+        self.time = time;
+        if elapsed == 0.0 || elapsed.abs() > RESEED_THRESHOLD {
+            return;
+        }
+
+        let acceleration = thrust * (SECONDS_PER_DAY * SECONDS_PER_DAY / METERS_PER_UNIT);
+        if self.landed {
+            // Solo se despega empujando hacia fuera de la superficie.
+            if acceleration.dot(&self.position) <= 0.0 {
+                return;
+            }
+            self.landed = false;
+        }
+
+        let mut current = time - elapsed;
+        let mut remaining = elapsed;
+        for substep in 0..MAX_SUBSTEPS {
+            let step = if substep + 1 == MAX_SUBSTEPS {
+                remaining
+            } else {
+                remaining.signum() * self.step_size(field).min(remaining.abs())
+            };
+
+            self.rk4_step(step, &acceleration, field);
+            current += step;
+            remaining -= step;
+
+            if self.touch_down(field) {
+                return;
+            }
+            self.update_frame(field, bodies, current);
+
+            if remaining.abs() < 1e-12 {
+                break;
+            }
+        }
+    }
+
+    /// Órbita osculante respecto al cuerpo de referencia; `None` en tierra.
+    pub fn orbit(&self, field: &GravityField) -> Option<OrbitalParameters> {
+        OrbitalParameters::from_state_vectors(
+            &self.position,
+            &self.velocity,
+            field.gm(self.reference),
+            self.time,
+        )
+    }
+
+    /// Trayectoria prevista sin empuje, relativa al cuerpo de referencia:
+    /// una vuelta de la cónica osculante, cortada al salir de la esfera de
+    /// influencia o al chocar con la superficie.
+    pub fn predict(&self, field: &GravityField, num_points: usize) -> Vec<DVec3> {
+        let Some(orbit) = self.orbit(field) else {
+            return Vec::new();
+        };
+        let Some(start) = orbit.true_anomaly(self.time) else {
+            return Vec::new();
+        };
+
+        let e = orbit.eccentricity;
+        let (span, max_radius) = if orbit.is_open() {
+            // Desde la posición actual hasta casi la asíntota.
+            let asymptote = if e > 1.0 { (-1.0 / e).acos() } else { PI };
+            let start = (start + PI).rem_euclid(2.0 * PI) - PI;
+            let extent = self.position.magnitude() * PREDICTION_EXTENT;
+            (asymptote * 0.999 - start, field.soi_radius(self.reference).min(extent))
+        } else {
+            (2.0 * PI, field.soi_radius(self.reference))
+        };
+
+        let semi_latus_rectum = orbit.semi_latus_rectum();
+        let surface = field.surface_radius(self.reference);
+        let (p, q) = orbit.perifocal_basis();
+        let steps = num_points.max(2) - 1;
+
+        let mut points = Vec::with_capacity(num_points);
+        for i in 0..=steps {
+            let true_anomaly = start + span * i as f64 / steps as f64;
+            let radius = semi_latus_rectum / (1.0 + e * true_anomaly.cos());
+            if radius > max_radius {
+                break;
+            }
+            points.push((p * true_anomaly.cos() + q * true_anomaly.sin()) * radius);
+            if radius < surface {
+                break;
+            }
+        }
+        points
+    }
+
+    fn step_size(&self, field: &GravityField) -> f64 {
+        let radius = self.position.magnitude();
+        let gm = field.gm(self.reference);
+        let speed = self.velocity.magnitude();
+
+        let mut characteristic = f64::INFINITY;
+        if gm > 0.0 {
+            characteristic = (radius.powi(3) / gm).sqrt();
+        }
+        if speed > 0.0 {
+            characteristic = characteristic.min(radius / speed);
+        }
+        characteristic * STEP_FRACTION
+    }
+
+    fn rk4_step(&mut self, dt: f64, thrust: &DVec3, field: &GravityField) {
+        let gm = field.gm(self.reference);
+        let surface = field.surface_radius(self.reference);
+        let accel = |position: &DVec3| gravity(position, gm, surface) + thrust;
+
+        let (p0, v0) = (self.position, self.velocity);
+        let a1 = accel(&p0);
+        let v1 = v0 + a1 * (dt / 2.0);
+        let a2 = accel(&(p0 + v0 * (dt / 2.0)));
+        let v2 = v0 + a2 * (dt / 2.0);
+        let a3 = accel(&(p0 + v1 * (dt / 2.0)));
+        let v3 = v0 + a3 * dt;
+        let a4 = accel(&(p0 + v2 * dt));
+
+        self.position = p0 + (v0 + v1 * 2.0 + v2 * 2.0 + v3) * (dt / 6.0);
+        self.velocity = v0 + (a1 + a2 * 2.0 + a3 * 2.0 + a4) * (dt / 6.0);
+    }
+
+    /// Detiene la nave sobre la superficie si la ha atravesado cayendo.
+    fn touch_down(&mut self, field: &GravityField) -> bool {
+        let surface = field.surface_radius(self.reference);
+        let radius = self.position.magnitude();
+        if radius >= surface || self.position.dot(&self.velocity) >= 0.0 {
+            return false;
+        }
+
+        if radius > 1e-9 {
+            self.position *= surface / radius;
+        }
+        self.velocity = DVec3::zeros();
+        self.landed = true;
+        true
+    }
+
+    /// Cambia de marco al salir de la esfera actual o entrar en la de un satélite.
+    fn update_frame(&mut self, field: &GravityField, bodies: &[CelestialBody], time: f64) {
+        let radius = self.position.magnitude();
+        if let Some(frame) = self.reference.filter(|&frame| radius > field.soi[frame]) {
+            let (position, velocity) = field.relative_state(bodies, frame, time);
+            self.position += position;
+            self.velocity += velocity;
+            self.reference = field.parent_frames[frame];
+            return;
+        }
+
+        for child in field.child_frames(self.reference) {
+            let (position, velocity) = field.relative_state(bodies, child, time);
+            if (self.position - position).magnitude() < field.soi[child] {
+                self.position -= position;
+                self.velocity -= velocity;
+                self.reference = Some(child);
+                return;
+            }
+        }
+    }
+}
+
+/// Velocidad de una órbita circular prograda (sentido antihorario visto desde +Y).
+fn circular_velocity(position: &DVec3, gm: f64) -> DVec3 {
+    let radius = position.magnitude();
+    if gm <= 0.0 || radius < 1e-9 {
+        return DVec3::zeros();
+    }
+
+    let mut direction = DVec3::y().cross(position);
+    if direction.magnitude() < 1e-9 * radius {
+        direction = DVec3::x().cross(position);
+    }
+    direction.normalize() * (gm / radius).sqrt()
+}
+
+/// Atracción de una masa `gm` en el origen; por dentro de `radius` crece
+/// linealmente con la distancia, como en una esfera uniforme.
+fn gravity(position: &DVec3, gm: f64, radius: f64) -> DVec3 {
+    let distance = position.magnitude().max(radius);
+    if distance < 1e-9 || gm == 0.0 {
+        return DVec3::zeros();
+    }
+    -position * (gm / (distance * distance * distance))
+}
+
+/// Convierte una velocidad de unidades/día a m/s.
+pub fn to_meters_per_second(speed: f64) -> f64 {
+    speed * METERS_PER_UNIT / SECONDS_PER_DAY
+}
@@ -1,4 +1,5 @@
 use nalgebra_glm::Vec3;
+use raylib::texture::Image;
 
 /// Representa un color RGB de 8 bits por canal.
 ///
@@ -43,15 +44,47 @@ impl Color {
     }
 }
 
+/// Filtro usado al escalar un framebuffer de resolución interna a la de salida.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpscaleFilter {
+    /// Interpolación bilineal de los cuatro píxeles vecinos.
+    Bilinear,
+    /// Bilineal en zonas suaves; en bordes de alto contraste toma el vecino
+    /// más parecido al resultado bilineal para no emborronar siluetas.
+    EdgeAware,
+}
+
+/// Distancia de vista más lejana que distingue el buffer de profundidad.
+pub const DEPTH_FAR: f32 = 5.0e6;
+
+/// Convierte una distancia de vista (la `w` de clip) en profundidad
+/// logarítmica en [0, 1].
+///
+/// La precisión relativa es constante en todo el rango, así que una nave a
+/// pocas unidades de la cámara y un planeta a millones de unidades comparten
+/// el mismo buffer sin z-fighting. Debe evaluarse por fragmento a partir de
+/// `w` interpolada con corrección de perspectiva: el logaritmo no es afín en
+/// espacio de pantalla.
+#[inline]
+pub fn log_depth(view_depth: f32) -> f32 {
+    (1.0 + view_depth.max(0.0)).log2() / (1.0 + DEPTH_FAR).log2()
+}
+
+/// Identificador reservado para los píxeles que no pertenecen a ningún objeto.
+pub const NO_OBJECT: u32 = u32::MAX;
+
 /// Framebuffer de software utilizado para el renderizado manual por píxeles.
 ///
 /// Contiene dos buffers paralelos:
 /// - `buffer`: almacena los valores de color RGBA.
 /// - `zbuffer`: gestiona la profundidad por píxel para el z-test.
 ///
-/// Los valores de profundidad se comparan directamente en espacio NDC,
-/// donde -1.0 representa el plano cercano (cerca de la cámara)
-/// y 1.0 el plano lejano.
+/// Opcionalmente mantiene un tercer buffer con el identificador del objeto
+/// visible en cada píxel (ver [`Framebuffer::enable_object_ids`]), que se usa
+/// para seleccionar cuerpos con el ratón.
+///
+/// El z-buffer guarda la profundidad logarítmica de [`log_depth`] en [0, 1],
+/// donde 0.0 corresponde a la posición de la cámara y 1.0 a [`DEPTH_FAR`].
 pub struct Framebuffer {
     /// Ancho de la imagen en píxeles.
     pub width: usize,
@@ -61,6 +94,13 @@ pub struct Framebuffer {
     pub buffer: Vec<u8>,
     /// Buffer de profundidad (z-buffer) con un valor por píxel.
     pub zbuffer: Vec<f32>,
+    /// Buffer opcional de identificadores de objeto (uno por píxel).
+    pub object_ids: Option<Vec<u32>>,
+    /// Identificador que `set_pixel` escribe en `object_ids` al pasar el z-test.
+    current_object_id: u32,
+    /// Cantidad de fragmentos rasterizados por píxel en el cuadro actual
+    /// (lo llena el renderer; alimenta las estadísticas y el modo de overdraw).
+    pub overdraw: Vec<u16>,
 }
 
 impl Framebuffer {
@@ -74,13 +114,172 @@ impl Framebuffer {
             height,
             buffer: vec![0; width * height * 4],
             zbuffer: vec![f32::INFINITY; width * height],
+            object_ids: None,
+            current_object_id: NO_OBJECT,
+            overdraw: vec![0; width * height],
+        }
+    }
+
+    /// Mezcla `color` sobre el píxel con opacidad `alpha` si pasa el z-test.
+    ///
+    /// No escribe profundidad ni identificador de objeto; se usa para líneas
+    /// antialiasadas, que deben quedar ocultas por los cuerpos pero no ocultarlos.
+    pub fn blend_pixel(&mut self, x: usize, y: usize, color: Color, alpha: f32, depth: f32) {
+        if x >= self.width || y >= self.height || !depth.is_finite() {
+            return;
+        }
+
+        let index = y * self.width + x;
+        if depth >= self.zbuffer[index] {
+            return;
+        }
+
+        let idx = index * 4;
+        let alpha = alpha.clamp(0.0, 1.0);
+        for (channel, value) in [color.r, color.g, color.b].into_iter().enumerate() {
+            let dst = self.buffer[idx + channel] as f32;
+            self.buffer[idx + channel] = (dst + (value as f32 - dst) * alpha) as u8;
+        }
+        self.buffer[idx + 3] = 255;
+    }
+
+    /// Cambia las dimensiones del framebuffer, reasignando todos sus buffers.
+    ///
+    /// El contenido se pierde; se usa con la resolución dinámica antes de
+    /// empezar a dibujar un cuadro.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if width == self.width && height == self.height {
+            return;
+        }
+
+        self.width = width;
+        self.height = height;
+        self.buffer = vec![0; width * height * 4];
+        self.zbuffer = vec![f32::INFINITY; width * height];
+        self.overdraw = vec![0; width * height];
+        if self.object_ids.is_some() {
+            self.object_ids = Some(vec![NO_OBJECT; width * height]);
+        }
+    }
+
+    /// Escala el color de este framebuffer sobre `target`, que puede tener
+    /// cualquier resolución (normalmente la de la ventana).
+    pub fn upscale_into(&self, target: &mut Framebuffer, filter: UpscaleFilter) {
+        if self.width == target.width && self.height == target.height {
+            target.buffer.copy_from_slice(&self.buffer);
+            return;
+        }
+
+        let scale_x = self.width as f32 / target.width as f32;
+        let scale_y = self.height as f32 / target.height as f32;
+
+        for ty in 0..target.height {
+            // Centro del píxel de destino en coordenadas de origen.
+            let sy = ((ty as f32 + 0.5) * scale_y - 0.5).max(0.0);
+            let y0 = (sy as usize).min(self.height - 1);
+            let y1 = (y0 + 1).min(self.height - 1);
+            let fy = sy - y0 as f32;
+
+            for tx in 0..target.width {
+                let sx = ((tx as f32 + 0.5) * scale_x - 0.5).max(0.0);
+                let x0 = (sx as usize).min(self.width - 1);
+                let x1 = (x0 + 1).min(self.width - 1);
+                let fx = sx - x0 as f32;
+
+                let samples = [
+                    self.color_at(x0, y0),
+                    self.color_at(x1, y0),
+                    self.color_at(x0, y1),
+                    self.color_at(x1, y1),
+                ];
+                let weights = [
+                    (1.0 - fx) * (1.0 - fy),
+                    fx * (1.0 - fy),
+                    (1.0 - fx) * fy,
+                    fx * fy,
+                ];
+
+                let mut blended = Vec3::zeros();
+                for (sample, weight) in samples.iter().zip(weights.iter()) {
+                    blended += sample * *weight;
+                }
+
+                let color = if filter == UpscaleFilter::EdgeAware {
+                    let lumas = samples.map(|c| c.dot(&Vec3::new(0.299, 0.587, 0.114)));
+                    let min_luma = lumas.iter().cloned().fold(f32::MAX, f32::min);
+                    let max_luma = lumas.iter().cloned().fold(f32::MIN, f32::max);
+
+                    if max_luma - min_luma > 48.0 {
+                        *samples
+                            .iter()
+                            .min_by(|a, b| {
+                                (*a - blended)
+                                    .norm_squared()
+                                    .partial_cmp(&(*b - blended).norm_squared())
+                                    .unwrap()
+                            })
+                            .unwrap()
+                    } else {
+                        blended
+                    }
+                } else {
+                    blended
+                };
+
+                let idx = (ty * target.width + tx) * 4;
+                target.buffer[idx] = color.x.clamp(0.0, 255.0) as u8;
+                target.buffer[idx + 1] = color.y.clamp(0.0, 255.0) as u8;
+                target.buffer[idx + 2] = color.z.clamp(0.0, 255.0) as u8;
+                target.buffer[idx + 3] = 255;
+            }
+        }
+    }
+
+    #[inline]
+    fn color_at(&self, x: usize, y: usize) -> Vec3 {
+        let idx = (y * self.width + x) * 4;
+        Vec3::new(
+            self.buffer[idx] as f32,
+            self.buffer[idx + 1] as f32,
+            self.buffer[idx + 2] as f32,
+        )
+    }
+
+    /// Activa el buffer de identificadores de objeto.
+    ///
+    /// A partir de este momento cada fragmento que pase el z-test registra
+    /// el identificador establecido con [`Framebuffer::set_object_id`].
+    pub fn enable_object_ids(&mut self) {
+        if self.object_ids.is_none() {
+            self.object_ids = Some(vec![NO_OBJECT; self.width * self.height]);
+        }
+    }
+
+    /// Establece el identificador del objeto que se está dibujando.
+    ///
+    /// Usar [`NO_OBJECT`] para geometría que no debe ser seleccionable.
+    #[inline]
+    pub fn set_object_id(&mut self, id: u32) {
+        self.current_object_id = id;
+    }
+
+    /// Devuelve el identificador del objeto visible en el píxel `(x, y)`,
+    /// o `None` si no hay objeto o el buffer de IDs está desactivado.
+    pub fn object_id_at(&self, x: usize, y: usize) -> Option<u32> {
+        if x >= self.width || y >= self.height {
+            return None;
         }
+
+        self.object_ids
+            .as_ref()
+            .map(|ids| ids[y * self.width + x])
+            .filter(|&id| id != NO_OBJECT)
     }
 
     /// Limpia el contenido del framebuffer con un color uniforme.
     ///
     /// También reinicia el z-buffer estableciendo todos los valores
-    /// a `f32::INFINITY`.
+    /// a `f32::INFINITY` y, si está activo, el buffer de identificadores.
     #[inline]
     pub fn clear(&mut self, color: Color) {
         for i in 0..self.width * self.height {
@@ -91,6 +290,12 @@ impl Framebuffer {
             self.buffer[idx + 3] = 255;
         }
         self.zbuffer.fill(f32::INFINITY);
+
+        if let Some(ids) = self.object_ids.as_mut() {
+            ids.fill(NO_OBJECT);
+        }
+        self.current_object_id = NO_OBJECT;
+        self.overdraw.fill(0);
     }
 
     /// Establece el color de un píxel específico en el framebuffer, aplicando z-test.
@@ -102,7 +307,7 @@ impl Framebuffer {
     /// * `x` - Coordenada X del píxel.
     /// * `y` - Coordenada Y del píxel.
     /// * `color` - Color a escribir.
-    /// * `depth` - Valor de profundidad Z (en espacio NDC).
+    /// * `depth` - Profundidad logarítmica en [0, 1] (ver [`log_depth`]).
     #[inline]
     pub fn set_pixel(&mut self, x: usize, y: usize, color: Color, depth: f32) {
         if x >= self.width || y >= self.height {
@@ -117,9 +322,12 @@ impl Framebuffer {
         let index = y * self.width + x;
 
         // Comparación de profundidad (z-test estándar).
-        // En NDC: -1.0 (cerca) → 1.0 (lejos).
+        // Profundidad logarítmica (ver `log_depth`): 0.0 (cámara) → 1.0 (DEPTH_FAR).
         if depth < self.zbuffer[index] {
             self.zbuffer[index] = depth;
+            if let Some(ids) = self.object_ids.as_mut() {
+                ids[index] = self.current_object_id;
+            }
             let idx = index * 4;
             self.buffer[idx] = color.r;
             self.buffer[idx + 1] = color.g;
@@ -134,4 +342,38 @@ impl Framebuffer {
     pub fn as_bytes(&self) -> &[u8] {
         &self.buffer
     }
-}
\ No newline at end of file
+
+    /// Guarda el buffer de color como PNG con el exportador de imágenes de
+    /// raylib, que trabaja en CPU y no necesita ventana.
+    ///
+    /// Se escribe primero en un archivo hermano temporal y luego se renombra
+    /// sobre `path`, de modo que una exportación fallida no borra la imagen
+    /// que ya hubiera en ese destino.
+    pub fn save_png(&self, path: &str) -> std::io::Result<()> {
+        // `GenImageColor` reserva un búfer R8G8B8A8 del mismo tamaño que el
+        // framebuffer, así que basta con copiar los bytes encima.
+        let image = Image::gen_image_color(
+            self.width as i32,
+            self.height as i32,
+            raylib::color::Color::BLACK,
+        );
+        let bytes = self.as_bytes();
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), image.data() as *mut u8, bytes.len());
+        }
+
+        // raylib decide el formato por la extensión: el temporal conserva la
+        // extensión `.png` al final.
+        let tmp_path = format!("{path}.tmp.png");
+        let c_tmp_path = std::ffi::CString::new(tmp_path.as_str())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let exported = unsafe { raylib::ffi::ExportImage(*image, c_tmp_path.as_ptr()) };
+        if !exported {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(std::io::Error::other(format!(
+                "raylib no pudo exportar la imagen a {tmp_path}"
+            )));
+        }
+        std::fs::rename(&tmp_path, path)
+    }
+}
@@ -0,0 +1,84 @@
+use crate::celestial_body::CelestialBody;
+
+/// Masas efectivas (GM en unidades³/día²) coherentes con las órbitas de la escena.
+///
+/// Las distancias de las lunas están exageradas respecto a las reales, así
+/// que el GM de cada cuerpo central se deduce de la tercera ley de Kepler con
+/// las órbitas de sus satélites; el de los cuerpos sin satélites guarda la
+/// misma proporción respecto a su central que sus masas reales
+/// ([`PhysicalProperties`](crate::celestial_body::PhysicalProperties)).
+pub struct GravityParameters {
+    /// GM con el que cada cuerpo atrae a los de su mismo nivel.
+    pub gm: Vec<f64>,
+    /// GM con el que cada cuerpo atrae a sus satélites.
+    pub satellite_gm: Vec<f64>,
+    /// GM del cuerpo central del nivel de cada cuerpo.
+    pub orbit_gm: Vec<f64>,
+}
+
+impl GravityParameters {
+    pub fn new(bodies: &[CelestialBody]) -> Self {
+        let count = bodies.len();
+
+        let mut gm = vec![0.0; count];
+        let mut satellite_gm = vec![0.0; count];
+        let mut orbit_gm = vec![0.0; count];
+
+        for (i, body) in bodies.iter().enumerate() {
+            let group_gm = level_gm(bodies, body.parent_index);
+            orbit_gm[i] = group_gm;
+            gm[i] = if body.orbital_params.is_none() {
+                // Cuerpo central del nivel raíz (el Sol).
+                group_gm
+            } else {
+                let central = real_central_gm(bodies, body.parent_index);
+                if central > 0.0 { group_gm * body.physical.gm / central } else { 0.0 }
+            };
+            satellite_gm[i] = level_gm(bodies, Some(i));
+        }
+
+        Self {
+            gm,
+            satellite_gm,
+            orbit_gm,
+        }
+    }
+
+    /// GM con el que el cuerpo `index` atrae a lo que se mueve a su alrededor:
+    /// el que fijan sus satélites si los tiene y, si no, el suyo propio.
+    pub fn attraction(&self, index: usize) -> f64 {
+        if self.satellite_gm[index] > 0.0 {
+            self.satellite_gm[index]
+        } else {
+            self.gm[index]
+        }
+    }
+}
+
+/// GM central de un nivel (por padre) a partir de n²a³ de sus órbitas.
+pub fn level_gm(bodies: &[CelestialBody], parent: Option<usize>) -> f64 {
+    let samples: Vec<f64> = bodies
+        .iter()
+        .filter(|b| b.parent_index == parent)
+        .filter_map(|b| b.orbital_params.as_ref())
+        .filter(|p| !p.is_open() && p.orbital_period > 0.0)
+        .map(|p| p.central_gm())
+        .collect();
+    if samples.is_empty() {
+        0.0
+    } else {
+        samples.iter().sum::<f64>() / samples.len() as f64
+    }
+}
+
+/// GM real del cuerpo central de un nivel, para las proporciones de masa.
+fn real_central_gm(bodies: &[CelestialBody], parent: Option<usize>) -> f64 {
+    match parent {
+        Some(p) => bodies[p].physical.gm,
+        None => bodies
+            .iter()
+            .find(|b| b.parent_index.is_none() && b.orbital_params.is_none())
+            .map(|b| b.physical.gm)
+            .unwrap_or(0.0),
+    }
+}
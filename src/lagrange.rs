@@ -0,0 +1,133 @@
+use crate::celestial_body::{CelestialBody, CelestialType};
+use crate::flight::GravityField;
+use crate::gravity::GravityParameters;
+use nalgebra_glm::DVec3;
+
+/// Iteraciones de bisección para los puntos colineales (precisión ~1e-15).
+const COLLINEAR_ITERATIONS: usize = 60;
+
+/// Estructura gravitatoria de un par primario/secundario (Sol–Tierra,
+/// Tierra–Luna...): puntos de Lagrange, esfera de Hill y esfera de influencia.
+///
+/// Los puntos se calculan en el problema restringido de tres cuerpos con la
+/// proporción de masas de [`GravityParameters`] y se colocan sobre la
+/// separación actual, así que siguen a las órbitas excéntricas.
+pub struct OrbitalPair {
+    pub primary: usize,
+    pub secondary: usize,
+    /// Distancias de L1, L2 y L3 al primario sobre la recta que lo une con
+    /// el secundario, en fracciones de la separación (L3 queda en negativo).
+    collinear: [f64; 3],
+    /// Radio de la esfera de Hill en el periapsis.
+    pub hill_radius: f64,
+    /// Radio de la esfera de influencia (0 si el cuerpo no es un marco del
+    /// vuelo newtoniano).
+    pub soi_radius: f64,
+}
+
+impl OrbitalPair {
+    /// Pares de todos los cuerpos con órbita cerrada y masa; los del nivel
+    /// raíz se emparejan con el cuerpo central del campo (el Sol).
+    pub fn all(bodies: &[CelestialBody], field: &GravityField) -> Vec<Self> {
+        let gravity = GravityParameters::new(bodies);
+
+        bodies
+            .iter()
+            .enumerate()
+            .filter(|(_, body)| body.body_type != CelestialType::Asteroid)
+            .filter_map(|(secondary, body)| {
+                let params = body.orbital_params.as_ref().filter(|p| !p.is_open())?;
+                let primary = body.parent_index.or(field.body(None))?;
+                let total_gm = gravity.orbit_gm[secondary] + gravity.gm[secondary];
+                if primary == secondary || gravity.gm[secondary] <= 0.0 || total_gm <= 0.0 {
+                    return None;
+                }
+
+                let mass_ratio = gravity.gm[secondary] / total_gm;
+                Some(Self {
+                    primary,
+                    secondary,
+                    collinear: collinear_points(mass_ratio),
+                    hill_radius: params.periapsis_distance()
+                        * (mass_ratio / (3.0 * (1.0 - mass_ratio))).cbrt(),
+                    soi_radius: field.soi_radius(Some(secondary)),
+                })
+            })
+            .collect()
+    }
+
+    /// Base ortonormal del plano orbital del secundario en `time`: la
+    /// dirección radial desde el primario y la dirección del movimiento
+    /// perpendicular a ella.
+    pub fn orbit_plane(&self, bodies: &[CelestialBody], world_positions: &[DVec3], time: f64) -> [DVec3; 2] {
+        let separation = world_positions[self.secondary] - world_positions[self.primary];
+        let radial = separation / separation.magnitude().max(1e-12);
+
+        let velocity = bodies[self.secondary]
+            .orbital_params
+            .as_ref()
+            .map(|params| params.get_state(time).1)
+            .unwrap_or_else(DVec3::zeros);
+        let normal = separation.cross(&velocity);
+        let normal = if normal.magnitude() > 1e-12 {
+            normal.normalize()
+        } else {
+            DVec3::new(0.0, 1.0, 0.0)
+        };
+        [radial, normal.cross(&radial)]
+    }
+
+    /// Posiciones absolutas de L1 a L5 en `time`.
+    ///
+    /// L4 va 60° por delante del secundario en su órbita y L5 60° por detrás.
+    pub fn lagrange_points(&self, bodies: &[CelestialBody], world_positions: &[DVec3], time: f64) -> [DVec3; 5] {
+        let origin = world_positions[self.primary];
+        let distance = (world_positions[self.secondary] - origin).magnitude();
+        let [radial, along_track] = self.orbit_plane(bodies, world_positions, time);
+
+        let collinear = |fraction: f64| origin + radial * (fraction * distance);
+        let triangular = |side: f64| {
+            origin + (radial * 0.5 + along_track * (side * 3f64.sqrt() / 2.0)) * distance
+        };
+        [
+            collinear(self.collinear[0]),
+            collinear(self.collinear[1]),
+            collinear(self.collinear[2]),
+            triangular(1.0),
+            triangular(-1.0),
+        ]
+    }
+}
+
+/// Puntos colineales del problema restringido de tres cuerpos, por bisección
+/// de la fuerza efectiva en el marco rotante (unidades de la separación, con
+/// el baricentro en el origen, el primario en -μ y el secundario en 1-μ).
+/// Se devuelven como distancias al primario.
+fn collinear_points(mass_ratio: f64) -> [f64; 3] {
+    let mu = mass_ratio;
+    let force = |x: f64| {
+        let (to_primary, to_secondary) = (x + mu, x - 1.0 + mu);
+        x - (1.0 - mu) * to_primary / to_primary.abs().powi(3)
+            - mu * to_secondary / to_secondary.abs().powi(3)
+    };
+
+    // En cada tramo la fuerza pasa de negativa a positiva.
+    let root = |mut low: f64, mut high: f64| {
+        for _ in 0..COLLINEAR_ITERATIONS {
+            let middle = 0.5 * (low + high);
+            if force(middle) < 0.0 {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        0.5 * (low + high) + mu
+    };
+
+    let gap = 1e-12;
+    [
+        root(-mu + gap, 1.0 - mu - gap),
+        root(1.0 - mu + gap, 2.0),
+        root(-2.0, -mu - gap),
+    ]
+}
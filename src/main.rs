@@ -10,31 +10,150 @@ mod ui;
 mod skybox;
 mod warp_effect;
 mod minimap;
+mod asteroid_belt;
+mod profiler;
+mod dynamic_resolution;
+mod culling;
+mod epoch;
+mod nbody;
+mod scenario;
+mod cli;
+mod scene_graph;
+mod gravity;
+mod flight;
+mod transfer_planner;
+mod porkchop;
+mod lagrange;
+mod small_bodies;
 
 use warp_effect::WarpEffect;
-use framebuffer::{Color, Framebuffer};
+use framebuffer::{Color, Framebuffer, UpscaleFilter, DEPTH_FAR, NO_OBJECT};
 use mesh::ObjMesh;
-use renderer::Renderer;
-use celestial_body::CelestialType;
+use renderer::{DebugView, Renderer};
+use celestial_body::{CelestialType, SurfaceShader};
 use solar_system::SolarSystemBuilder;
-use camera::SpaceshipCamera;
+use camera::{camera_relative, project_to_screen, SpaceshipCamera};
 use shaders::*;
 use trail::ShipTrail;
 use ui::GameUI;
 use skybox::Skybox;
 use minimap::Minimap;
-
-use nalgebra_glm::{Vec3, perspective};
+use profiler::{Profiler, Stage};
+use dynamic_resolution::{DynamicResolution, TemporalAccumulator};
+use culling::{Frustum, OcclusionCuller};
+use epoch::CalendarDate;
+use nbody::NBodySystem;
+use scenario::{Scenario, DEFAULT_ASTEROID_COUNT, DEFAULT_SEED};
+use cli::CliOptions;
+use scene_graph::SceneGraph;
+use flight::{GravityField, NewtonianShip};
+use transfer_planner::TransferPlan;
+use porkchop::Porkchop;
+use lagrange::OrbitalPair;
+
+use nalgebra_glm::{DVec3, Vec3, perspective};
 use raylib::prelude::*;
 
-use crate::celestial_body::CelestialBody;
-
 const WIDTH: usize = 1280;
 const HEIGHT: usize = 720;
 
 fn main() {
+    let options = match CliOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, CliOptions::usage());
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", CliOptions::usage());
+        return;
+    }
+
     println!("=== Iniciando Sistema Solar ===");
 
+    // =================== SISTEMA SOLAR ===================
+    println!("Creando sistema solar...");
+    let (scenario_name, celestial_bodies, asteroid_count, scenario_seed) = match &options.scenario {
+        Some(path) => {
+            let loaded = Scenario::load(path).and_then(|scenario| {
+                Ok((scenario.name.clone(), scenario.build()?, scenario.asteroid_count, scenario.seed))
+            });
+            match loaded {
+                Ok(loaded) => {
+                    println!("✓ Escenario cargado desde {}", path);
+                    loaded
+                }
+                Err(e) => {
+                    eprintln!("⚠ {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => (
+            "Sistema Solar".to_string(),
+            SolarSystemBuilder::build_realistic(),
+            DEFAULT_ASTEROID_COUNT,
+            None,
+        ),
+    };
+    // Una sola semilla para todo lo aleatorio: la misma ejecución se repite
+    // en capturas, grabaciones e informes de errores.
+    let seed = options.seed.or(scenario_seed).unwrap_or(DEFAULT_SEED);
+    println!("✓ Sistema solar creado con {} cuerpos (semilla {})", celestial_bodies.len(), seed);
+
+    let scene_graph = match SceneGraph::new(&celestial_bodies) {
+        Ok(graph) => graph,
+        Err(e) => {
+            eprintln!("⚠ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let focus = match &options.focus {
+        Some(name) => match scene_graph.find(name) {
+            Some(id) => Some(id),
+            None => {
+                eprintln!("⚠ No existe ningún cuerpo llamado '{}'", name);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    if let Some(path) = &options.save_scenario {
+        match Scenario::from_bodies(&scenario_name, &celestial_bodies, asteroid_count, seed).save(path) {
+            Ok(()) => println!("✓ Escenario guardado en {}", path),
+            Err(e) => {
+                eprintln!("⚠ {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some((origin_name, target_name)) = &options.porkchop {
+        let find = |name: &str| {
+            scene_graph.find(name).unwrap_or_else(|| {
+                eprintln!("⚠ No existe ningún cuerpo llamado '{}'", name);
+                std::process::exit(1);
+            })
+        };
+        let (origin, target) = (find(origin_name), find(target_name));
+        let start = options.porkchop_start.unwrap_or_else(epoch::now);
+        let path = options.porkchop_output.as_deref().unwrap_or("porkchop.png");
+
+        println!("Calculando diagrama porkchop...");
+        match Porkchop::compute(&celestial_bodies, origin, target, start).and_then(|plot| plot.export(path)) {
+            Ok(summary) => println!("✓ Diagrama guardado en {}\n{}", path, summary),
+            Err(e) => {
+                eprintln!("⚠ {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     let (mut rl, thread) = raylib::init()
         .size(WIDTH as i32, HEIGHT as i32)
         .title("Sistema Solar - Software Renderer")
@@ -50,7 +169,6 @@ fn main() {
     let sphere_mesh_medium = ObjMesh::create_sphere(1.0, 64, 64);   // Era 32
     let sphere_mesh_low = ObjMesh::create_sphere(1.0, 32, 32);      // Era 16
     let sphere_mesh_very_low = ObjMesh::create_sphere(1.0, 16, 16);
-    let ring_mesh = ObjMesh::create_ring(1.3, 2.0, 100);
 
     println!("Cargando modelo de nave...");
     let ship_mesh = match ObjMesh::load_from_obj("assets/ship.obj") {
@@ -77,35 +195,53 @@ fn main() {
         }
     };
 
-    let get_sphere_lod = |distance: f32, radius: f32| -> &ObjMesh {
+    // Devuelve la malla elegida y su nivel de detalle (0 = máximo).
+    let get_sphere_lod = |distance: f32, radius: f32| -> (&ObjMesh, usize) {
         let ratio = distance / radius;
 
         // println!("  LOD: dist={:.0}, radius={:.0}, ratio={:.1}", distance, radius, ratio);
         
         if ratio < 10.0 {
-            high_quality_sphere.as_ref().unwrap_or(&sphere_mesh_high)
+            (high_quality_sphere.as_ref().unwrap_or(&sphere_mesh_high), 0)
         } else if ratio < 50.0 {
-            &sphere_mesh_high
+            (&sphere_mesh_high, 1)
         } else if ratio < 200.0 {
-            &sphere_mesh_medium
+            (&sphere_mesh_medium, 2)
         } else if ratio < 500.0 {
-            &sphere_mesh_low
+            (&sphere_mesh_low, 3)
         } else {
-            &sphere_mesh_very_low
+            (&sphere_mesh_very_low, 4)
         }
     };
 
-    // =================== SISTEMA SOLAR ===================
-    println!("Creando sistema solar...");
-    let celestial_bodies = SolarSystemBuilder::build_realistic();
-    println!("✓ Sistema solar creado con {} cuerpos", celestial_bodies.len());
-
-    let mut camera = SpaceshipCamera::new(Vec3::new(0.0, 500.0, 8000.0));
+    // Una malla de anillos por cuerpo anillado, con sus radios propios.
+    let ring_meshes: Vec<Option<ObjMesh>> = celestial_bodies
+        .iter()
+        .map(|body| {
+            body.rings
+                .as_ref()
+                .map(|rings| ObjMesh::create_ring(rings.inner_radius, rings.outer_radius, 100))
+        })
+        .collect();
+
+    let mut asteroid_belt = SolarSystemBuilder::create_asteroid_belt(&celestial_bodies, asteroid_count, seed);
+    println!("✓ Cinturón de asteroides con {} instancias", asteroid_belt.positions().len());
+
+    let mut camera = SpaceshipCamera::new(DVec3::new(0.0, 500.0, 8000.0));
+    let gravity_field = GravityField::new(&celestial_bodies);
+    let orbital_pairs = OrbitalPair::all(&celestial_bodies, &gravity_field);
     let mut warp_effect = WarpEffect::new();
 
     // =================== FRAMEBUFFER + TEXTURA ===================
     let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
-    let renderer = Renderer::new(WIDTH, HEIGHT);
+    framebuffer.enable_object_ids();
+    let mut renderer = Renderer::new(WIDTH, HEIGHT);
+
+    // El render se hace a resolución interna variable y se escala a la de salida.
+    let mut output_framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    let mut dynamic_resolution = DynamicResolution::new(WIDTH, HEIGHT, 14.0);
+    let mut temporal = TemporalAccumulator::new();
+    let mut upscale_filter = UpscaleFilter::EdgeAware;
 
     let initial_image = Image::gen_image_color(
         WIDTH as i32, 
@@ -117,24 +253,39 @@ fn main() {
     // =================== TRAIL, SKYBOX, FLAGS ===================
     println!("Inicializando sistemas visuales...");
     let mut ship_trail = ShipTrail::new(200);
-    let skybox = Skybox::new(2000);
+    let skybox = Skybox::new(2000, seed);
 
     let mut show_trail = true;
     let mut minimap = Minimap::new(200);
     let mut show_minimap = true;
     let mut show_info = true;
+    let mut profiler = Profiler::new(240);
 
     println!("✓ Trail inicializado");
     println!("✓ Skybox generado");
 
     // =================== VARIABLES ===================
     let mut paused = false;
-    let mut simulation_time = 0.0f32;
+    let mut simulation_time = 0.0f64; // Días desde J2000 (TDB)
     let mut frame_time = 0.0f32;
     let mut show_orbits = true;
     let mut show_menu = false;
-    let time_scale = 0.001;
+    let time_scale = 0.001f64;
+    let mut real_time = false; // Avanza al ritmo del reloj (1 s simulado = 1 s real)
+    let mut date_input: Option<String> = None; // Fecha que se está escribiendo (J)
+    let mut date_error: Option<String> = None;
+    let mut nbody: Option<NBodySystem> = None; // Integración de N cuerpos en lugar de Kepler (P)
+    let mut ship: Option<NewtonianShip> = None; // Vuelo newtoniano en lugar del arcade (U)
+    let mut transfer_origin: Option<usize> = None; // Planificador de transferencias (Y)
+    let mut transfer_target: Option<usize> = None;
+    let mut transfer_plan: Option<Result<TransferPlan, String>> = None;
+    let mut transfer_planned_at = 0.0; // Tiempo de simulación con el que se calculó
+    let mut show_lagrange = false; // Puntos de Lagrange (F2)
+    let mut show_spheres = false; // Esferas de Hill y de influencia (F9)
     let mut menu_page = 0; // Página actual del menú
+    let mut selected_body: Option<usize> = focus; // Cuerpo elegido con el ratón
+    let mut locked_on = focus.is_some(); // La nave sigue apuntando al cuerpo seleccionado
+    let mut pointer_mode = false; // Cursor libre para seleccionar con el ratón
     const ITEMS_PER_PAGE: usize = 10; // Máximo 10 destinos por página (0-9)
 
     println!("=== Sistema iniciado correctamente ===\n");
@@ -142,6 +293,9 @@ fn main() {
     // =================== LOOP PRINCIPAL ===================
     while !rl.window_should_close() {
         frame_time += rl.get_frame_time();
+        renderer.begin_frame();
+        // Mientras se escribe una fecha las teclas que producen texto no disparan acciones.
+        let typing_date = date_input.is_some();
 
         // ------------ Control de tiempo ------------
         let mut current_time_scale = time_scale;
@@ -152,33 +306,138 @@ fn main() {
             current_time_scale *= 2.0;
         }
         if rl.is_key_down(KeyboardKey::KEY_KP_SUBTRACT) 
-            || rl.is_key_down(KeyboardKey::KEY_MINUS)
+            || (rl.is_key_down(KeyboardKey::KEY_MINUS) && !typing_date)
         {
             current_time_scale *= 0.5;
         }
 
         if !paused {
-            simulation_time += current_time_scale;
+            if real_time {
+                let multiplier = current_time_scale / time_scale;
+                simulation_time += rl.get_frame_time() as f64 / 86400.0 * multiplier;
+            } else {
+                simulation_time += current_time_scale;
+            }
         }
 
         // ------------ Calcular posiciones de cuerpos (MOVER AQUÍ) ------------
-        let mut world_positions = Vec::new();
-
-        for body in celestial_bodies.iter() {
-            let parent_pos = body.parent_index.map(|p| world_positions[p]);
-            world_positions.push(
-                body.get_world_position(simulation_time, parent_pos)
-            );
-        }
-        minimap.auto_zoom(&world_positions);
-        let collision_data: Vec<(Vec3, f32)> = celestial_bodies
+        profiler.start(Stage::OrbitUpdate);
+        let world_positions = match nbody.as_mut() {
+            Some(system) => {
+                system.advance_to(&celestial_bodies, simulation_time);
+                system.world_positions(&scene_graph)
+            }
+            None => scene_graph.world_positions(&celestial_bodies, simulation_time),
+        };
+        asteroid_belt.update_positions(simulation_time);
+        profiler.stop();
+        // El minimapa es una vista cenital a escala: le basta la precisión simple.
+        let map_positions: Vec<Vec3> = world_positions.iter().map(|p| p.cast::<f32>()).collect();
+        minimap.auto_zoom(&map_positions);
+        let collision_data: Vec<(DVec3, f32)> = celestial_bodies
             .iter()
             .enumerate()
             .map(|(i, _body)| (world_positions[i], celestial_bodies[i].radius))
             .collect();
 
+        // ------------ Fecha de la simulación ------------
+        if let Some(text) = date_input.as_mut() {
+            while let Some(c) = rl.get_char_pressed() {
+                if (c.is_ascii_digit() || matches!(c, '-' | ':' | ' ')) && text.len() < 19 {
+                    text.push(c);
+                }
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+                text.pop();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                match CalendarDate::parse(text) {
+                    Ok(date) => {
+                        simulation_time = date.to_simulation_time();
+                        date_input = None;
+                        date_error = None;
+                    }
+                    Err(e) => date_error = Some(e),
+                }
+            } else if rl.is_key_pressed(KeyboardKey::KEY_J) {
+                date_input = None;
+                date_error = None;
+            }
+        } else if !show_menu {
+            if rl.is_key_pressed(KeyboardKey::KEY_J) {
+                date_input = Some(String::new());
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_N) {
+                simulation_time = epoch::now();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_R) {
+                real_time = !real_time;
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_P) {
+                nbody = match nbody {
+                    Some(_) => None,
+                    None => Some(NBodySystem::new(&celestial_bodies, simulation_time)),
+                };
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_U) {
+                ship = match ship {
+                    Some(_) => None,
+                    None => Some(NewtonianShip::new(
+                        camera.position,
+                        simulation_time,
+                        &gravity_field,
+                        &world_positions,
+                    )),
+                };
+                camera.newtonian = ship.is_some();
+            }
+        }
+
+        // ------------ Masa del cuerpo seleccionado (N cuerpos) ------------
+        if let (Some(system), Some(idx)) = (nbody.as_mut(), selected_body) {
+            if rl.is_key_pressed(KeyboardKey::KEY_X) {
+                system.scale_mass(idx, 10.0);
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_Z) {
+                system.scale_mass(idx, 0.1);
+            }
+        }
+
+        // ------------ Planificador de transferencias ------------
+        // Y fija el origen con el cuerpo seleccionado, luego el destino; sin
+        // selección cierra el planificador.
+        if rl.is_key_pressed(KeyboardKey::KEY_Y) {
+            match (selected_body, transfer_origin, transfer_target) {
+                (Some(idx), Some(_), None) => transfer_target = Some(idx),
+                (Some(idx), _, _) => {
+                    transfer_origin = Some(idx);
+                    transfer_target = None;
+                }
+                (None, _, _) => {
+                    transfer_origin = None;
+                    transfer_target = None;
+                }
+            }
+            transfer_plan = None;
+        }
+        // El plan se calcula una vez por par y solo se rehace cuando la ventana
+        // ya pasó o el tiempo salta hacia atrás: recalcularlo en cada cuadro
+        // estrecha la búsqueda y la mejor salida cambiaría de un cuadro a otro.
+        let stale_plan = match &transfer_plan {
+            None => true,
+            Some(Ok(plan)) => simulation_time > plan.departure_time || simulation_time < transfer_planned_at,
+            Some(Err(_)) => false,
+        };
+        if let (true, Some(origin), Some(target)) = (stale_plan, transfer_origin, transfer_target) {
+            transfer_plan = Some(TransferPlan::new(&celestial_bodies, origin, target, simulation_time));
+            transfer_planned_at = simulation_time;
+        }
+        if let (true, Some(Ok(plan))) = (rl.is_key_pressed(KeyboardKey::KEY_HOME), &transfer_plan) {
+            simulation_time = plan.departure_time;
+        }
+
         // ------------ Entradas globales ------------
-        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
+        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) && !typing_date {
             paused = !paused;
         }
         if rl.is_key_pressed(KeyboardKey::KEY_O) {
@@ -193,20 +452,73 @@ fn main() {
         if rl.is_key_pressed(KeyboardKey::KEY_I) {
             show_info = !show_info;
         }
-        if rl.is_key_pressed(KeyboardKey::KEY_TAB) {
+        if rl.is_key_pressed(KeyboardKey::KEY_F3) {
+            renderer.debug_view = renderer.debug_view.next();
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F4) {
+            profiler.visible = !profiler.visible;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F6) {
+            dynamic_resolution.enabled = !dynamic_resolution.enabled;
+        }
+        // Sin reproyección el historial solo sirve con la escena quieta: la
+        // acumulación se activa en pausa y puede apagarse en cualquier momento.
+        if rl.is_key_pressed(KeyboardKey::KEY_F7) && (paused || temporal.enabled) {
+            temporal.enabled = !temporal.enabled;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F8) {
+            upscale_filter = match upscale_filter {
+                UpscaleFilter::Bilinear => UpscaleFilter::EdgeAware,
+                UpscaleFilter::EdgeAware => UpscaleFilter::Bilinear,
+            };
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F2) {
+            show_lagrange = !show_lagrange;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F9) {
+            show_spheres = !show_spheres;
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F5) {
+            let stamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let path = format!("perfil_{}.csv", stamp);
+            match profiler.dump_csv(&path) {
+                Ok(()) => println!("✓ Perfil exportado a {}", path),
+                Err(e) => println!("⚠ No se pudo exportar el perfil: {}", e),
+            }
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_TAB) && !typing_date {
             show_menu = !show_menu;
-            if show_menu { rl.enable_cursor(); }
+            if show_menu || pointer_mode { rl.enable_cursor(); }
+            else { rl.disable_cursor(); }
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_V) && !show_menu {
+            pointer_mode = !pointer_mode;
+            if pointer_mode { rl.enable_cursor(); }
             else { rl.disable_cursor(); }
         }
 
+        // ------------ Acciones sobre el cuerpo seleccionado ------------
+        if let Some(idx) = selected_body {
+            if rl.is_key_pressed(KeyboardKey::KEY_ENTER) && !warp_effect.is_active() && !typing_date {
+                warp_effect.start_warp(camera.position, world_positions[idx], 2.0);
+                ship_trail.clear();
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_B) {
+                locked_on = !locked_on;
+            }
+        }
+
         // ------------ Teleportación ------------
         if show_menu {
 
-            // Filtrar cuerpos no-asteroides
-            let menu_bodies: Vec<(usize, &CelestialBody)> = celestial_bodies
-                .iter()
-                .enumerate()
-                .filter(|(_, body)| body.body_type != CelestialType::Asteroid)
+            // Filtrar cuerpos no-asteroides, cada uno seguido de sus satélites
+            let menu_bodies: Vec<(usize, usize)> = scene_graph
+                .depth_first()
+                .into_iter()
+                .filter(|&(id, _)| celestial_bodies[id].body_type != CelestialType::Asteroid)
                 .collect();
 
             let start_idx = menu_page * ITEMS_PER_PAGE;
@@ -242,7 +554,7 @@ fn main() {
                     let item_idx = start_idx + key_num;
 
                     if item_idx < end_idx {
-                        let (real_idx, _body) = menu_bodies[item_idx];
+                        let (real_idx, _depth) = menu_bodies[item_idx];
                         let target = world_positions[real_idx];
 
                         // Iniciar warp animado
@@ -251,7 +563,9 @@ fn main() {
                         ship_trail.clear();
                         show_menu = false;
                         menu_page = 0; // Resetear página al cerrar
-                        rl.disable_cursor();
+                        if !pointer_mode {
+                            rl.disable_cursor();
+                        }
                     }
                 }
             }
@@ -261,12 +575,30 @@ fn main() {
             if let Some(warp_pos) = warp_effect.update(rl.get_frame_time()) {
                 camera.position = warp_pos;
                 camera.sync_smoothed_position();
+
+                // En vuelo newtoniano se llega en órbita alrededor del destino.
+                if let Some(ship) = ship.as_mut() {
+                    *ship = NewtonianShip::new(warp_pos, simulation_time, &gravity_field, &world_positions);
+                }
             }
 
             // Control manual normal si no estamos en warp
             if !warp_effect.is_active() {
                 camera.update(&rl);
-                camera.check_collisions(&collision_data);
+                match ship.as_mut() {
+                    Some(ship) => {
+                        let thrust = camera.movement_input(&rl).cast::<f64>()
+                            * ship.thrust
+                            * SpaceshipCamera::boost(&rl) as f64;
+                        ship.update(thrust, simulation_time, &gravity_field, &celestial_bodies);
+                        camera.follow(ship.world_position(&gravity_field, &world_positions));
+                    }
+                    None => camera.check_collisions(&collision_data),
+                }
+
+                if let (true, Some(idx)) = (locked_on, selected_body) {
+                    camera.look_at_point(world_positions[idx]);
+                }
 
                 if show_trail && !paused {
                     ship_trail.update(camera.position, frame_time);
@@ -274,102 +606,108 @@ fn main() {
             }
         }
 
+        // ------------ Resolución interna ------------
+        let render_start = std::time::Instant::now();
+        let (internal_width, internal_height) = dynamic_resolution.internal_size();
+        framebuffer.resize(internal_width, internal_height);
+        renderer.resize(internal_width, internal_height);
+
         // ------------ Matrices ------------
+        // La vista solo rota: la escena se rebasa en f64 a la posición de la cámara.
         let view_matrix = camera.get_view_matrix();
-        let projection_matrix = perspective(
-            WIDTH as f32 / HEIGHT as f32,
-            60.0_f32.to_radians(), 
-            50.0, 
-            5000000.0,
+        let camera_pos = camera.get_camera_position();
+        temporal.begin_frame(&view_matrix, &camera_pos, simulation_time);
+        // Los shaders trabajan en f32; la conversión se hace solo aquí.
+        let shader_time = simulation_time as f32;
+        let projection_matrix = temporal.jitter_projection(
+            &perspective(
+                WIDTH as f32 / HEIGHT as f32,
+                60.0_f32.to_radians(),
+                0.01,
+                DEPTH_FAR,
+            ),
+            internal_width,
+            internal_height,
         );
 
+        // Un único frustum por cuadro para cuerpos y cinturón.
+        let frustum = Frustum::from_matrix(&(projection_matrix * view_matrix));
+
         // ------------ Limpiar framebuffer ------------
         framebuffer.clear(Color::new(5, 5, 15));
 
         // ------------ Skybox ------------
+        profiler.start(Stage::Skybox);
         skybox.render(
             &mut framebuffer,
             &view_matrix,
             &projection_matrix,
-            WIDTH as f32,
-            HEIGHT as f32,
+            renderer.width,
+            renderer.height,
         );
+        profiler.stop();
 
-        // ------------ Órbitas ------------
-        if show_orbits {
-            for (_i, body) in celestial_bodies.iter().enumerate() {
-                if body.body_type == CelestialType::Asteroid {
-                    continue;
-                }
-                
-                if body.body_type != CelestialType::Star {
-                    let orbit_points = body.get_orbit_points(100);
-                    let parent_pos = body.parent_index
-                        .map(|p| world_positions[p])
-                        .unwrap_or(Vec3::zeros());
-                    
-                    let orbit_color = match body.body_type {
-                        CelestialType::Moon => Color::new(80, 80, 100),
-                        _ => Color::new(100, 100, 150),
-                    };
-                    
-                    renderer.render_orbit(
-                        &mut framebuffer,
-                        &orbit_points,
-                        parent_pos,
-                        &view_matrix,
-                        &projection_matrix,
-                        orbit_color,
-                    );
-                }
+        // ------------ Render de cuerpos ------------
+        profiler.start(Stage::Bodies);
+
+        // Estrellas y planetas actúan como oclusores de lo que quede detrás.
+        let mut occlusion = OcclusionCuller::new(&view_matrix);
+        for (i, body) in celestial_bodies.iter().enumerate() {
+            if matches!(body.body_type, CelestialType::Star | CelestialType::Planet) {
+                occlusion.add_occluder(i, &camera_relative(&world_positions[i], &camera_pos), body.radius);
             }
         }
 
-        // ------------ Render de cuerpos ------------
-        let camera_pos = camera.get_camera_position();
-
         for (i, body) in celestial_bodies.iter().enumerate() {
-            let world_pos = world_positions[i];
-            let dist = (world_pos - camera_pos).magnitude();
+            let world_pos = camera_relative(&world_positions[i], &camera_pos);
+            let dist = world_pos.magnitude();
 
             if dist < body.radius * 1.5 {
                 continue;
             }
 
-            if !renderer.is_in_frustum(&world_pos, body.radius, &view_matrix, &projection_matrix) {
+            // Los anillos sobresalen de la esfera del planeta.
+            let bounding_radius = body.bounding_radius();
+
+            if !renderer.is_in_frustum(&frustum, &world_pos, bounding_radius) {
+                continue;
+            }
+
+            if renderer.is_occluded(&occlusion, i, &world_pos, bounding_radius) {
                 continue;
             }
 
-            let lod_mesh = if body.body_type == CelestialType::Planet 
+            let (lod_mesh, lod_level) = if body.body_type == CelestialType::Planet 
                 && body.body_type != CelestialType::Asteroid {
                 // Planetas principales siempre alta calidad
                 if dist < body.radius * 100.0 {
-                    high_quality_sphere.as_ref().unwrap_or(&sphere_mesh_high)
+                    (high_quality_sphere.as_ref().unwrap_or(&sphere_mesh_high), 0)
                 } else {
-                    &sphere_mesh_medium
+                    (&sphere_mesh_medium, 2)
                 }
             } else {
                 get_sphere_lod(dist, body.radius)
             };
+            renderer.set_lod_level(Some(lod_level));
             let model_matrix = body.get_model_matrix(simulation_time, world_pos);
 
-            let shader: Box<dyn PlanetShader> = match body.body_type {
-                CelestialType::Star => Box::new(ClassicSunShader),
-                CelestialType::Planet => match body.name.as_str() {
-                    "Mercurio" => Box::new(MercuryShader),
-                    "Venus" => Box::new(VenusShader),
-                    "Tierra" => Box::new(EarthShader),
-                    "Marte" => Box::new(MarsShader),
-                    "Júpiter" => Box::new(JupiterShader),
-                    "Saturno" => Box::new(SaturnShader),
-                    "Urano" => Box::new(UranusShader),
-                    "Neptuno" => Box::new(NeptuneShader),
-                    _ => Box::new(RockyPlanet),
-                }
-                CelestialType::Moon => Box::new(MoonShader),
-                CelestialType::Asteroid => Box::new(AsteroidShader),
+            let shader: Box<dyn PlanetShader> = match body.shader {
+                SurfaceShader::Sun => Box::new(ClassicSunShader),
+                SurfaceShader::Mercury => Box::new(MercuryShader),
+                SurfaceShader::Venus => Box::new(VenusShader),
+                SurfaceShader::Earth => Box::new(EarthShader),
+                SurfaceShader::Mars => Box::new(MarsShader),
+                SurfaceShader::Jupiter => Box::new(JupiterShader),
+                SurfaceShader::Saturn => Box::new(SaturnShader),
+                SurfaceShader::Uranus => Box::new(UranusShader),
+                SurfaceShader::Neptune => Box::new(NeptuneShader),
+                SurfaceShader::Rocky => Box::new(RockyPlanet),
+                SurfaceShader::Moon => Box::new(MoonShader),
+                SurfaceShader::Asteroid => Box::new(AsteroidShader),
             };
 
+            framebuffer.set_object_id(i as u32);
+
             renderer.render_mesh(
                 &mut framebuffer,
                 lod_mesh,
@@ -377,70 +715,273 @@ fn main() {
                 &model_matrix,
                 &view_matrix,
                 &projection_matrix,
-                simulation_time,
+                shader_time,
             );
 
-            if body.name == "Saturno" && dist < body.radius * 50.0 {
+            // Los anillos solo se dibujan de cerca.
+            let ring_mesh = ring_meshes[i].as_ref().filter(|_| dist < body.radius * 50.0);
+            if let (Some(rings), Some(ring_mesh)) = (&body.rings, ring_mesh) {
                 let ring_model = nalgebra_glm::rotate(
                     &model_matrix,
-                    20.0_f32.to_radians(),
-                    &Vec3::new(1.0, 0.0, 0.3),
+                    rings.tilt,
+                    &rings.tilt_axis,
                 );
                 
                 // Usar render_ring en lugar de render_mesh
                 renderer.render_ring(
                     &mut framebuffer,
-                    &ring_mesh,
+                    ring_mesh,
                     &RingShader,
                     &ring_model,
                     &view_matrix,
                     &projection_matrix,
-                    simulation_time,
+                    shader_time,
                 );
             }
         }
 
-        // ------------ Trail ------------
-        if show_trail {
-            ship_trail.render(
-                &mut framebuffer,
-                &renderer,
-                &view_matrix,
-                &projection_matrix,
-            );
-        }
+        framebuffer.set_object_id(NO_OBJECT);
+        renderer.set_lod_level(None);
+
+        // ------------ Cinturón de asteroides (instanciado) ------------
+        asteroid_belt.render(
+            &mut framebuffer,
+            &renderer,
+            &AsteroidShader,
+            &view_matrix,
+            &projection_matrix,
+            &frustum,
+            &camera_pos,
+            simulation_time,
+        );
 
         // ------------ Nave 3ra persona ------------
+        profiler.start(Stage::Ship);
         if camera.third_person {
             if let Some(ship) = &ship_mesh {
-                let ship_projection = perspective(
-                    WIDTH as f32 / HEIGHT as f32,
-                    60.0_f32.to_radians(),
-                    0.01,
-                    100.0,
-                );
-
                 let ship_scale = 0.35;
-                let ship_model = camera.get_ship_model_matrix_fixed(ship_scale);
+                let ship_model = camera.get_ship_model_matrix_fixed(ship_scale, &camera_pos);
 
-                // SIEMPRE usar overlay en tercera persona
-                renderer.render_mesh_overlay(
+                // Con profundidad logarítmica la nave comparte proyección y
+                // z-buffer con el resto de la escena.
+                renderer.render_mesh(
                     &mut framebuffer,
                     ship,
                     &SimpleMetallicShader,
                     &ship_model,
                     &view_matrix,
-                    &ship_projection,
-                    simulation_time,
+                    &projection_matrix,
+                    shader_time,
                 );
             }
         }
 
+        // ------------ Órbitas ------------
+        // Las líneas y superposiciones prueban la profundidad pero no la
+        // escriben: van después de toda la geometría opaca para que los
+        // cuerpos, los anillos y la nave tapen los tramos que quedan detrás.
+        profiler.start(Stage::Orbits);
+        if show_orbits {
+            for (i, body) in celestial_bodies.iter().enumerate() {
+                if body.body_type == CelestialType::Asteroid {
+                    continue;
+                }
+                
+                if body.body_type != CelestialType::Star {
+                    let orbit_points = body.get_orbit_points(100, simulation_time);
+                    let parent_pos = scene_graph.parent(i)
+                        .map(|p| camera_relative(&world_positions[p], &camera_pos))
+                        .unwrap_or_else(|| camera_relative(&DVec3::zeros(), &camera_pos));
+                    
+                    let is_open = body.orbital_params.as_ref().is_some_and(|p| p.is_open());
+                    let orbit_color = match body.body_type {
+                        _ if is_open => Color::new(150, 110, 80),
+                        CelestialType::Moon => Color::new(80, 80, 100),
+                        _ => Color::new(100, 100, 150),
+                    };
+                    
+                    if is_open {
+                        renderer.render_path(
+                            &mut framebuffer,
+                            &orbit_points,
+                            parent_pos,
+                            &view_matrix,
+                            &projection_matrix,
+                            orbit_color,
+                        );
+                    } else {
+                        renderer.render_orbit(
+                            &mut framebuffer,
+                            &orbit_points,
+                            parent_pos,
+                            &view_matrix,
+                            &projection_matrix,
+                            orbit_color,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Transferencia planificada: elipse completa tenue y tramo de vuelo.
+        if let Some(Ok(TransferPlan { origin, lambert: Some(lambert), .. })) = &transfer_plan {
+            let central_pos = scene_graph.parent(*origin)
+                .map(|p| camera_relative(&world_positions[p], &camera_pos))
+                .unwrap_or_else(|| camera_relative(&DVec3::zeros(), &camera_pos));
+
+            if !lambert.orbit.is_open() {
+                let ellipse: Vec<Vec3> = (0..100)
+                    .map(|i| {
+                        let t = lambert.departure_time + lambert.orbit.orbital_period * i as f64 / 100.0;
+                        lambert.orbit.get_position(t).cast::<f32>()
+                    })
+                    .collect();
+                renderer.render_orbit(
+                    &mut framebuffer,
+                    &ellipse,
+                    central_pos,
+                    &view_matrix,
+                    &projection_matrix,
+                    Color::new(110, 70, 30),
+                );
+            }
+
+            let arc: Vec<Vec3> = lambert.arc_points(100).iter().map(|p| p.cast::<f32>()).collect();
+            renderer.render_path(
+                &mut framebuffer,
+                &arc,
+                central_pos,
+                &view_matrix,
+                &projection_matrix,
+                Color::new(255, 160, 40),
+            );
+        }
+
+        // Trayectoria prevista de la nave, relativa a su cuerpo de referencia.
+        let (path_origin, predicted_path) = match &ship {
+            Some(ship) => (
+                gravity_field.origin(ship.reference, &world_positions),
+                ship.predict(&gravity_field, 200),
+            ),
+            None => (DVec3::zeros(), Vec::new()),
+        };
+        if !predicted_path.is_empty() {
+            let path_points: Vec<Vec3> = predicted_path.iter().map(|p| p.cast::<f32>()).collect();
+            renderer.render_path(
+                &mut framebuffer,
+                &path_points,
+                camera_relative(&path_origin, &camera_pos),
+                &view_matrix,
+                &projection_matrix,
+                Color::new(80, 220, 120),
+            );
+        }
+
+        // Estructura gravitatoria: con un cuerpo seleccionado, solo sus pares.
+        // Cada par lleva sus puntos de Lagrange y la base de su plano orbital,
+        // que comparten el dibujo 3D, las etiquetas y el minimapa.
+        let overlay_pairs: Vec<(&OrbitalPair, [DVec3; 5], [DVec3; 2])> = if show_lagrange || show_spheres {
+            orbital_pairs
+                .iter()
+                .filter(|pair| selected_body.is_none_or(|id| pair.primary == id || pair.secondary == id))
+                .map(|pair| {
+                    (
+                        pair,
+                        pair.lagrange_points(&celestial_bodies, &world_positions, simulation_time),
+                        pair.orbit_plane(&celestial_bodies, &world_positions, simulation_time),
+                    )
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        for (pair, points, plane) in &overlay_pairs {
+            if show_lagrange {
+                let markers: Vec<(Vec3, f32, Color)> = points
+                    .iter()
+                    .map(|p| (camera_relative(p, &camera_pos), 4.0, Color::new(230, 120, 255)))
+                    .collect();
+                renderer.render_points(&mut framebuffer, &markers, &view_matrix, &projection_matrix);
+            }
+            if show_spheres {
+                let center = camera_relative(&world_positions[pair.secondary], &camera_pos);
+                // El círculo se dibuja en el plano orbital del par, no en xz,
+                // para que las lunas inclinadas y Plutón lo lleven consigo.
+                let [radial, along_track] = plane.map(|axis| axis.cast::<f32>());
+                for (radius, color) in [
+                    (pair.hill_radius, Color::new(80, 200, 220)),
+                    (pair.soi_radius, Color::new(220, 180, 60)),
+                ] {
+                    if radius <= 0.0 {
+                        continue;
+                    }
+                    let ring: Vec<Vec3> = (0..64)
+                        .map(|i| {
+                            let angle = i as f32 / 64.0 * std::f32::consts::TAU;
+                            (radial * angle.cos() + along_track * angle.sin()) * radius as f32
+                        })
+                        .collect();
+                    renderer.render_orbit(&mut framebuffer, &ring, center, &view_matrix, &projection_matrix, color);
+                }
+            }
+        }
+
+        profiler.stop();
+
+        // ------------ Trail ------------
+        profiler.start(Stage::Trail);
+        if show_trail {
+            renderer.line_width = 2.0;
+            ship_trail.render(
+                &mut framebuffer,
+                &renderer,
+                &view_matrix,
+                &projection_matrix,
+                &camera_pos,
+            );
+            renderer.line_width = 1.0;
+        }
+        profiler.stop();
+
+        // ------------ Modos de depuración de cuadro completo ------------
+        profiler.start(Stage::Post);
+        renderer.resolve_debug_view(&mut framebuffer);
+
         // ------------ Efecto de Warp (ANTES de actualizar textura) ------------
         warp_effect.render(&mut framebuffer);
 
+        // ------------ Escalado a resolución de salida ------------
+        framebuffer.upscale_into(&mut output_framebuffer, upscale_filter);
+        temporal.resolve(&mut output_framebuffer);
+        profiler.stop();
+        dynamic_resolution.update(render_start.elapsed().as_secs_f32() * 1000.0);
+
+        // ------------ Selección con el ratón (buffer de IDs) ------------
+        if !show_menu && rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            // Sin cursor libre se selecciona lo que esté bajo la mira central.
+            let (pick_x, pick_y) = if pointer_mode {
+                let mouse = rl.get_mouse_position();
+                (mouse.x.max(0.0) as usize, mouse.y.max(0.0) as usize)
+            } else {
+                (WIDTH / 2, HEIGHT / 2)
+            };
+            // El buffer de IDs está a resolución interna.
+            let pick_x = pick_x * framebuffer.width / WIDTH;
+            let pick_y = pick_y * framebuffer.height / HEIGHT;
+
+            selected_body = framebuffer
+                .object_id_at(pick_x, pick_y)
+                .map(|id| id as usize);
+
+            if selected_body.is_none() {
+                locked_on = false;
+            }
+        }
+
         // ===== ACTUALIZAR TEXTURA (ANTES DE begin_drawing) =====
-        texture.update_texture(framebuffer.as_bytes()).ok();
+        profiler.start(Stage::TextureUpload);
+        texture.update_texture(output_framebuffer.as_bytes()).ok();
+        profiler.end_frame(rl.get_frame_time(), renderer.frame_stats(&framebuffer));
 
         if show_minimap {
             minimap.handle_input(&rl);
@@ -486,10 +1027,61 @@ fn main() {
         );
 
         d.draw_text(
-            &format!("Tiempo: {:.1}x", current_time_scale / time_scale),
+            &format!(
+                "Tiempo: {:.1}x{}",
+                current_time_scale / time_scale,
+                if real_time { " (tiempo real)" } else { "" }
+            ),
             10, 100, 16, raylib::color::Color::SKYBLUE
         );
 
+        // ----- Fecha de la simulación -----
+        d.draw_text(
+            &CalendarDate::from_simulation_time(simulation_time).to_string(),
+            10, HEIGHT as i32 - 50, 16, raylib::color::Color::SKYBLUE
+        );
+        d.draw_text(
+            &format!("JD {:.5}   Semilla {}", epoch::julian_date(simulation_time), seed),
+            10, HEIGHT as i32 - 28, 14, raylib::color::Color::LIGHTGRAY
+        );
+
+        if let Some(text) = &date_input {
+            GameUI::draw_date_input(&mut d, text, date_error.as_deref(), WIDTH as i32, HEIGHT as i32);
+        }
+
+        // ----- Etiquetas de la estructura gravitatoria -----
+        let label_view_projection = perspective(
+            WIDTH as f32 / HEIGHT as f32,
+            60.0_f32.to_radians(),
+            0.01,
+            DEPTH_FAR,
+        ) * view_matrix;
+        let project_label = |point: &DVec3| {
+            project_to_screen(&camera_relative(point, &camera_pos), &label_view_projection, WIDTH as f32, HEIGHT as f32)
+        };
+        for (pair, points, [radial, _]) in &overlay_pairs {
+            let name = &celestial_bodies[pair.secondary].name;
+            if show_lagrange {
+                for (n, point) in points.iter().enumerate() {
+                    if let Some((x, y)) = project_label(point) {
+                        d.draw_text(&format!("L{} {}", n + 1, name), x + 5, y - 5, 10, raylib::color::Color::new(230, 120, 255, 220));
+                    }
+                }
+            }
+            if show_spheres {
+                for (label, radius, color) in [
+                    ("Hill", pair.hill_radius, raylib::color::Color::new(80, 200, 220, 220)),
+                    ("SOI", pair.soi_radius, raylib::color::Color::new(220, 180, 60, 220)),
+                ] {
+                    // Sobre el eje radial del plano orbital, donde empieza el círculo.
+                    let edge = world_positions[pair.secondary] + radial * radius;
+                    if let (true, Some((x, y))) = (radius > 0.0, project_label(&edge)) {
+                        d.draw_text(&format!("{} {}", label, name), x + 4, y - 5, 10, color);
+                    }
+                }
+            }
+        }
+
         // ----- Advertencia de colisión -----
         if let Some((idx, distance, severity)) = camera.get_collision_warning(&collision_data) {
             let body = &celestial_bodies[idx];
@@ -517,6 +1109,12 @@ fn main() {
             );
         }
 
+        // Órbita que muestra el panel: la de Kepler o la osculante de la integración.
+        let body_orbit = |idx: usize| match &nbody {
+            Some(system) => system.osculating_orbit(idx, simulation_time),
+            None => celestial_bodies[idx].orbital_params.clone(),
+        };
+
         // ----- Info del cuerpo más cercano -----
         if let Some((idx, distance)) = nearest_body {
             let body = &celestial_bodies[idx];
@@ -526,8 +1124,8 @@ fn main() {
                 10, 120, 16, raylib::color::Color::YELLOW
             );
 
-            if show_info && distance < 50000.0 {
-                GameUI::draw_planet_info(&mut d, body, distance, speed);
+            if show_info && distance < 50000.0 && selected_body.is_none() {
+                GameUI::draw_planet_info(&mut d, body, body_orbit(idx).as_ref(), distance, speed, simulation_time);
             }
 
             if distance < body.radius * 3.0 {
@@ -554,29 +1152,167 @@ fn main() {
             }
         }
 
+        // ----- Cuerpo seleccionado -----
+        if let Some(idx) = selected_body {
+            let body = &celestial_bodies[idx];
+            let distance = (world_positions[idx] - camera.position).magnitude() as f32;
+
+            let info_bottom = GameUI::draw_planet_info(&mut d, body, body_orbit(idx).as_ref(), distance, speed, simulation_time);
+            GameUI::draw_selection_actions(&mut d, locked_on, info_bottom + 10);
+        }
+
+        // ----- Mira central -----
+        if !pointer_mode && !show_menu {
+            let cx = WIDTH as i32 / 2;
+            let cy = HEIGHT as i32 / 2;
+            let crosshair_color = raylib::color::Color::new(200, 220, 255, 120);
+            d.draw_line(cx - 8, cy, cx - 3, cy, crosshair_color);
+            d.draw_line(cx + 3, cy, cx + 8, cy, crosshair_color);
+            d.draw_line(cx, cy - 8, cx, cy - 3, crosshair_color);
+            d.draw_line(cx, cy + 3, cx, cy + 8, crosshair_color);
+        }
+
         // ----- Minimapa -----
         if show_minimap {      
             minimap.render(
                 &mut d,
                 WIDTH as i32,
                 HEIGHT as i32,
-                &world_positions,
+                &map_positions,
                 &celestial_bodies,
-                &camera.position,
+                asteroid_belt.positions(),
+                &camera.position.cast::<f32>(),
                 &camera.forward,
                 frame_time,
             );
+
+            let map_path: Vec<Vec3> = predicted_path
+                .iter()
+                .map(|p| (path_origin + p).cast::<f32>())
+                .collect();
+            minimap.draw_path(
+                &mut d,
+                WIDTH as i32,
+                HEIGHT as i32,
+                &map_path,
+                raylib::color::Color::new(80, 220, 120, 200),
+            );
+
+            for (pair, points, _) in &overlay_pairs {
+                let name = &celestial_bodies[pair.secondary].name;
+                if show_lagrange {
+                    for (n, point) in points.iter().enumerate() {
+                        minimap.draw_marker(
+                            &mut d,
+                            WIDTH as i32,
+                            HEIGHT as i32,
+                            &point.cast::<f32>(),
+                            &format!("L{}", n + 1),
+                            raylib::color::Color::new(230, 120, 255, 220),
+                        );
+                    }
+                }
+                if show_spheres {
+                    for (label, radius, color) in [
+                        ("Hill", pair.hill_radius, raylib::color::Color::new(80, 200, 220, 200)),
+                        ("SOI", pair.soi_radius, raylib::color::Color::new(220, 180, 60, 200)),
+                    ] {
+                        let top = minimap.draw_circle(
+                            &mut d,
+                            WIDTH as i32,
+                            HEIGHT as i32,
+                            &map_positions[pair.secondary],
+                            radius as f32,
+                            color,
+                        );
+                        if let Some(top) = top {
+                            d.draw_text(&format!("{} {}", label, name), top.x as i32 + 3, top.y as i32 - 9, 8, color);
+                        }
+                    }
+                }
+            }
         }
 
+        // ----- Perfilador -----
+        profiler.draw(&mut d, WIDTH as i32 - 430, 60);
+
         // ----- Indicadores -----
         if paused {
             d.draw_text("[PAUSADO]", 10, 160, 20, raylib::color::Color::RED);
         }
 
+        if let Some(system) = &nbody {
+            let mass_text = match selected_body {
+                Some(idx) if system.mass_scale(idx) != 1.0 => format!(
+                    " - {} masa x{}",
+                    celestial_bodies[idx].name,
+                    system.mass_scale(idx)
+                ),
+                _ => String::new(),
+            };
+            d.draw_text(
+                &format!("FÍSICA: N CUERPOS{}", mass_text),
+                10, 182, 16, raylib::color::Color::ORANGE
+            );
+        }
+
+        if let Some(origin) = transfer_origin {
+            GameUI::draw_transfer_plan(
+                &mut d,
+                &celestial_bodies,
+                origin,
+                transfer_plan.as_ref(),
+                simulation_time,
+                WIDTH as i32 - 350,
+                80,
+            );
+        }
+
+        if let Some(ship) = &ship {
+            let reference_name = gravity_field
+                .body(ship.reference)
+                .map(|idx| celestial_bodies[idx].name.as_str())
+                .unwrap_or("-");
+            GameUI::draw_flight_status(&mut d, ship, &gravity_field, reference_name, 10, HEIGHT as i32 - 125);
+        }
+
         if show_trail {
             d.draw_text("TRAIL: ON", WIDTH as i32 - 120, 10, 14, raylib::color::Color::GREEN);
         }
 
+        if dynamic_resolution.enabled || temporal.enabled {
+            let filter_label = match upscale_filter {
+                UpscaleFilter::Bilinear => "bilineal",
+                UpscaleFilter::EdgeAware => "bordes",
+            };
+            d.draw_text(
+                &format!(
+                    "RES: {}x{} ({:.0}%) {}{}",
+                    framebuffer.width,
+                    framebuffer.height,
+                    dynamic_resolution.scale() * 100.0,
+                    filter_label,
+                    if temporal.is_accumulating() { " +TAA" } else { "" }
+                ),
+                WIDTH as i32 - 260, 50, 14, raylib::color::Color::SKYBLUE
+            );
+        }
+
+        // Con el tiempo corriendo la TAA queda en espera y se muestra atenuada.
+        if temporal.enabled && !paused {
+            d.draw_text(
+                "TAA: en espera (solo en pausa)",
+                WIDTH as i32 - 260, 70, 14, raylib::color::Color::GRAY
+            );
+        }
+
+        if renderer.debug_view != DebugView::None {
+            d.draw_text(
+                &format!("DEBUG: {}", renderer.debug_view.label()),
+                WIDTH as i32 - 220, 30, 16, raylib::color::Color::MAGENTA
+            );
+        }
+
         // ----- Menú de teleportación -----
         if show_menu {
             // Fondo semitransparente
@@ -588,10 +1324,10 @@ fn main() {
                 40, 80, 20, raylib::color::Color::LIGHTGRAY);
 
             // Construcción del menú filtrado
-            let menu_bodies: Vec<(usize, &CelestialBody)> = celestial_bodies
-                .iter()
-                .enumerate()
-                .filter(|(_, body)| body.body_type != CelestialType::Asteroid)
+            let menu_bodies: Vec<(usize, usize)> = scene_graph
+                .depth_first()
+                .into_iter()
+                .filter(|&(id, _)| celestial_bodies[id].body_type != CelestialType::Asteroid)
                 .collect();
 
 
@@ -602,11 +1338,11 @@ fn main() {
             // Dibujar los ítems visibles
             let mut y = 140;
             for (display_idx, item_idx) in (start_idx..end_idx).enumerate() {
-                let (_real_idx, body) = menu_bodies[item_idx];
+                let (real_idx, depth) = menu_bodies[item_idx];
 
-                // Texto como: 0. Earth
-                let label = format!("{}. {}", display_idx, body.name);
-                d.draw_text(&label, 60, y, 26, raylib::color::Color::WHITE);
+                // Texto como: 0. Earth (los satélites, sangrados bajo su planeta)
+                let label = format!("{}. {}", display_idx, celestial_bodies[real_idx].name);
+                d.draw_text(&label, 60 + depth as i32 * 30, y, 26, raylib::color::Color::WHITE);
 
                 y += 40;
             }
@@ -627,7 +1363,7 @@ fn main() {
             let help_x = WIDTH as i32 / 2 - 150;
             let help_y = 100;
 
-            d.draw_rectangle(help_x - 10, help_y - 10, 320, 280, raylib::color::Color::new(0,0,0,200));
+            d.draw_rectangle(help_x - 10, help_y - 10, 320, 460, raylib::color::Color::new(0,0,0,200));
 
             d.draw_text("AYUDA RÁPIDA", help_x, help_y, 18, raylib::color::Color::YELLOW);
             d.draw_text("T - Toggle Trail", help_x, help_y + 30, 14, raylib::color::Color::WHITE);
@@ -638,8 +1374,28 @@ fn main() {
             d.draw_text("F/G/H - Modos Warp", help_x, help_y + 130, 14, raylib::color::Color::WHITE);
             d.draw_text("TAB - Teleportación", help_x, help_y + 150, 14, raylib::color::Color::WHITE);
             d.draw_text("SPACE - Pausar", help_x, help_y + 170, 14, raylib::color::Color::WHITE);
+            d.draw_text("Click - Seleccionar / V - Puntero", help_x, help_y + 190, 14, raylib::color::Color::WHITE);
+            d.draw_text("ENTER - Viajar / B - Fijar objetivo", help_x, help_y + 210, 14, raylib::color::Color::WHITE);
+            d.draw_text("F3 - Modos de depuración", help_x, help_y + 230, 14, raylib::color::Color::WHITE);
+            d.draw_text("F4 / F5 - Perfil / Exportar CSV", help_x, help_y + 250, 14, raylib::color::Color::WHITE);
+
+            d.draw_text("F6/F8 - Res. dinámica / Filtro", help_x, help_y + 270, 14, raylib::color::Color::WHITE);
+
+            d.draw_text("J / N / R - Ir a fecha / Ahora / Tiempo real", help_x, help_y + 290, 14, raylib::color::Color::WHITE);
+
+            d.draw_text("P - N cuerpos / X, Z - Masa x10 / x0.1", help_x, help_y + 310, 14, raylib::color::Color::WHITE);
+
+            d.draw_text("U - Vuelo newtoniano", help_x, help_y + 330, 14, raylib::color::Color::WHITE);
+
+            d.draw_text("Y / INICIO - Transferencia / Ir a la salida", help_x, help_y + 350, 14, raylib::color::Color::WHITE);
+
+            d.draw_text("F2 / F9 - Lagrange / Esferas de Hill y SOI", help_x, help_y + 370, 14, raylib::color::Color::WHITE);
+
+            // La TAA solo se puede activar en pausa: con el tiempo corriendo se atenúa.
+            let taa_color = if paused { raylib::color::Color::WHITE } else { raylib::color::Color::GRAY };
+            d.draw_text("F7 - TAA (solo en pausa)", help_x, help_y + 390, 14, taa_color);
 
-            d.draw_text("Mantén F1 para ver ayuda", help_x - 30, help_y + 220, 12, raylib::color::Color::GRAY);
+            d.draw_text("Mantén F1 para ver ayuda", help_x - 30, help_y + 420, 12, raylib::color::Color::GRAY);
         } else {
             d.draw_text("F1 - Ayuda", WIDTH as i32 - 100, HEIGHT as i32 - 25, 14, raylib::color::Color::GRAY);
         }
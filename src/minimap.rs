@@ -1,4 +1,4 @@
-use nalgebra_glm::Vec3;
+use nalgebra_glm::{DVec3, Vec3};
 use raylib::prelude::*;
 use crate::celestial_body::{CelestialBody, CelestialType};
 
@@ -52,6 +52,7 @@ impl Minimap {
         screen_height: i32,
         bodies_positions: &[Vec3],
         bodies: &[CelestialBody],
+        asteroid_positions: &[DVec3],
         camera_pos: &Vec3,
         camera_forward: &Vec3,
         time: f32,
@@ -71,6 +72,7 @@ impl Minimap {
             self.draw_orbits(d, center_x, center_y, bodies);
         }
 
+        self.draw_asteroids(d, center_x, center_y, asteroid_positions);
         self.draw_sun(d, center_x, center_y);
         self.draw_celestial_bodies(d, center_x, center_y, bodies_positions, bodies, camera_pos);
         self.draw_ship(d, center_x, center_y, camera_pos, camera_forward);
@@ -84,6 +86,88 @@ impl Minimap {
         self.draw_controls_hint(d, map_x, map_y);
     }
 
+    /// Dibuja una trayectoria (posiciones absolutas) sobre el minimapa ya
+    /// renderizado; los tramos que salen del mapa se omiten.
+    pub fn draw_path(
+        &self,
+        d: &mut RaylibDrawHandle,
+        screen_width: i32,
+        screen_height: i32,
+        points: &[Vec3],
+        color: RaylibColor,
+    ) {
+        let half_size = self.size / 2;
+        let center_x = screen_width - self.size - 10 + half_size;
+        let center_y = screen_height - self.size - 10 + half_size;
+        let to_screen = |pos: &Vec3| {
+            Vector2::new(
+                center_x as f32 + pos.x / self.zoom_level * half_size as f32,
+                center_y as f32 + pos.z / self.zoom_level * half_size as f32,
+            )
+        };
+        let inside = |p: &Vector2| {
+            (p.x - center_x as f32).abs() <= half_size as f32
+                && (p.y - center_y as f32).abs() <= half_size as f32
+        };
+
+        for segment in points.windows(2) {
+            let (start, end) = (to_screen(&segment[0]), to_screen(&segment[1]));
+            if inside(&start) && inside(&end) {
+                d.draw_line_ex(start, end, 1.5, color);
+            }
+        }
+    }
+
+    /// Dibuja un punto con su etiqueta (posición absoluta) sobre el minimapa
+    /// ya renderizado, si cae dentro.
+    pub fn draw_marker(
+        &self,
+        d: &mut RaylibDrawHandle,
+        screen_width: i32,
+        screen_height: i32,
+        position: &Vec3,
+        label: &str,
+        color: RaylibColor,
+    ) {
+        if let Some(point) = self.project(screen_width, screen_height, position) {
+            d.draw_circle(point.x as i32, point.y as i32, 2.0, color);
+            d.draw_text(label, point.x as i32 + 4, point.y as i32 - 4, 8, color);
+        }
+    }
+
+    /// Dibuja una circunferencia de radio `radius` (unidades) centrada en una
+    /// posición absoluta. Se omite si el centro cae fuera del mapa o si a esta
+    /// escala no se distingue del punto; si se dibuja, devuelve su punto más
+    /// alto para colocar una etiqueta.
+    pub fn draw_circle(
+        &self,
+        d: &mut RaylibDrawHandle,
+        screen_width: i32,
+        screen_height: i32,
+        center: &Vec3,
+        radius: f32,
+        color: RaylibColor,
+    ) -> Option<Vector2> {
+        let point = self.project(screen_width, screen_height, center)?;
+        let screen_radius = radius / self.zoom_level * (self.size / 2) as f32;
+        if screen_radius < 3.0 || screen_radius > self.size as f32 {
+            return None;
+        }
+        d.draw_circle_lines(point.x as i32, point.y as i32, screen_radius, color);
+        Some(Vector2::new(point.x, point.y - screen_radius))
+    }
+
+    /// Posición en pantalla de un punto absoluto, si cae dentro del mapa.
+    fn project(&self, screen_width: i32, screen_height: i32, position: &Vec3) -> Option<Vector2> {
+        let half_size = self.size / 2;
+        let center_x = (screen_width - self.size - 10 + half_size) as f32;
+        let center_y = (screen_height - self.size - 10 + half_size) as f32;
+        let offset_x = position.x / self.zoom_level * half_size as f32;
+        let offset_y = position.z / self.zoom_level * half_size as f32;
+        (offset_x.abs() <= half_size as f32 && offset_y.abs() <= half_size as f32)
+            .then(|| Vector2::new(center_x + offset_x, center_y + offset_y))
+    }
+
     fn draw_background(&self, d: &mut RaylibDrawHandle, x: i32, y: i32) {
         d.draw_rectangle(
             x - 5,
@@ -148,8 +232,9 @@ impl Minimap {
                 continue;
             }
 
-            if let Some(ref params) = body.orbital_params {
-                let radius = (params.semi_major_axis / self.zoom_level * (self.size as f32 / 2.0)) as i32;
+            // Las órbitas abiertas no tienen una circunferencia representativa.
+            if let Some(params) = body.orbital_params.as_ref().filter(|p| !p.is_open()) {
+                let radius = (params.semi_major_axis as f32 / self.zoom_level * (self.size as f32 / 2.0)) as i32;
                 
                 if radius > 5 && radius < self.size / 2 {
                     let orbit_color = RaylibColor::new(60, 80, 120, 100);
@@ -159,6 +244,29 @@ impl Minimap {
         }
     }
 
+    fn draw_asteroids(
+        &self,
+        d: &mut RaylibDrawHandle,
+        center_x: i32,
+        center_y: i32,
+        asteroid_positions: &[DVec3],
+    ) {
+        // Con miles de asteroides basta una muestra para dibujar la forma del cinturón.
+        const MAX_DRAWN: usize = 1500;
+        let step = asteroid_positions.len().div_ceil(MAX_DRAWN).max(1);
+        let half_size = self.size / 2;
+        let color = RaylibColor::new(120, 100, 90, 120);
+
+        for pos in asteroid_positions.iter().step_by(step) {
+            let screen_x = center_x + (pos.x as f32 / self.zoom_level * half_size as f32) as i32;
+            let screen_y = center_y + (pos.z as f32 / self.zoom_level * half_size as f32) as i32;
+
+            if (screen_x - center_x).abs() <= half_size && (screen_y - center_y).abs() <= half_size {
+                d.draw_pixel(screen_x, screen_y, color);
+            }
+        }
+    }
+
     fn draw_sun(&self, d: &mut RaylibDrawHandle, center_x: i32, center_y: i32) {
         for i in 0..5 {
             let glow_radius = 8.0 + i as f32 * 2.0;
@@ -0,0 +1,245 @@
+use crate::celestial_body::{CelestialBody, OrbitalParameters};
+use crate::gravity::GravityParameters;
+use crate::scene_graph::SceneGraph;
+use nalgebra_glm::DVec3;
+
+/// Paso máximo de integración (días). Fobos da una vuelta en 0.32 días, así
+/// que con este paso cada órbita se resuelve en más de 150 pasos.
+const MAX_STEP: f64 = 0.002;
+/// Límite de subpasos por llamada; por encima el paso crece y se pierde precisión.
+const MAX_SUBSTEPS: usize = 4000;
+/// Saltos de tiempo mayores que este (días) no se integran: se reinicia el
+/// estado desde los elementos de Kepler en la nueva fecha.
+const RESEED_THRESHOLD: f64 = 30.0;
+
+/// Coeficientes del integrador de Yoshida de cuarto orden.
+const YOSHIDA_W1: f64 = 1.351_207_191_959_657_8; // 1 / (2 - 2^(1/3))
+const YOSHIDA_W0: f64 = -1.702_414_383_919_315_3; // -2^(1/3) · w1
+const YOSHIDA_DRIFT: [f64; 4] = [
+    YOSHIDA_W1 / 2.0,
+    (YOSHIDA_W0 + YOSHIDA_W1) / 2.0,
+    (YOSHIDA_W0 + YOSHIDA_W1) / 2.0,
+    YOSHIDA_W1 / 2.0,
+];
+const YOSHIDA_KICK: [f64; 3] = [YOSHIDA_W1, YOSHIDA_W0, YOSHIDA_W1];
+
+/// Integración gravitatoria de N cuerpos.
+///
+/// Alternativa a los raíles de Kepler: cada cuerpo se mueve bajo la gravedad
+/// mutua de los de su mismo nivel con un integrador simpléctico de Yoshida
+/// (cuarto orden), partiendo de las posiciones y velocidades que dan sus
+/// elementos orbitales.
+///
+/// Las distancias de las lunas están exageradas respecto a las de los
+/// planetas, así que cada sistema de satélites se integra en el marco de su
+/// planeta: las lunas sienten al planeta y a sus hermanas, pero no al Sol.
+/// Las masas son las de [`GravityParameters`], con las que las órbitas
+/// iniciales son estables.
+pub struct NBodySystem {
+    time: f64,
+    parents: Vec<Option<usize>>,
+    /// Posiciones relativas al padre (absolutas para el nivel raíz).
+    positions: Vec<DVec3>,
+    velocities: Vec<DVec3>,
+    /// GM con el que cada cuerpo atrae a los de su mismo nivel.
+    gm: Vec<f64>,
+    /// GM con el que cada cuerpo atrae a sus satélites.
+    satellite_gm: Vec<f64>,
+    /// GM del cuerpo central de cada nivel al crear el sistema.
+    orbit_gm: Vec<f64>,
+    /// Radio visual de cada cuerpo: por dentro su atracción deja de crecer,
+    /// para que un paso rasante no dispare la aceleración.
+    radii: Vec<f64>,
+    mass_scale: Vec<f64>,
+    accelerations: Vec<DVec3>,
+}
+
+impl NBodySystem {
+    /// Crea el sistema a partir de los elementos de Kepler en `time`.
+    pub fn new(bodies: &[CelestialBody], time: f64) -> Self {
+        let count = bodies.len();
+        let parents: Vec<Option<usize>> = bodies.iter().map(|b| b.parent_index).collect();
+
+        let GravityParameters {
+            gm,
+            satellite_gm,
+            orbit_gm,
+        } = GravityParameters::new(bodies);
+
+        let mut system = Self {
+            time,
+            parents,
+            positions: vec![DVec3::zeros(); count],
+            velocities: vec![DVec3::zeros(); count],
+            gm,
+            satellite_gm,
+            orbit_gm,
+            radii: bodies.iter().map(|b| b.radius as f64).collect(),
+            mass_scale: vec![1.0; count],
+            accelerations: vec![DVec3::zeros(); count],
+        };
+        system.reset(bodies, time);
+        system
+    }
+
+    /// Reinicia posiciones y velocidades desde los elementos de Kepler en
+    /// `time`, conservando las masas modificadas.
+    pub fn reset(&mut self, bodies: &[CelestialBody], time: f64) {
+        for (i, body) in bodies.iter().enumerate() {
+            let (position, velocity) = match &body.orbital_params {
+                Some(params) => {
+                    let (position, mut velocity) = params.get_state(time);
+
+                    // Cada órbita implica su propio GM; reescalar la velocidad
+                    // al GM común conserva la forma de la cónica.
+                    let implied_gm = params.at_time(time).central_gm();
+                    if implied_gm > 0.0 && self.orbit_gm[i] > 0.0 {
+                        velocity *= (self.orbit_gm[i] / implied_gm).sqrt();
+                    }
+                    (position, velocity)
+                }
+                None => (DVec3::zeros(), DVec3::zeros()),
+            };
+            self.positions[i] = position;
+            self.velocities[i] = velocity;
+        }
+
+        // El cuerpo central del nivel raíz compensa el momento del resto para
+        // que el baricentro no derive.
+        if let Some(central) = self.root_central() {
+            let momentum: DVec3 = (0..bodies.len())
+                .filter(|&i| self.parents[i].is_none() && i != central)
+                .map(|i| self.velocities[i] * self.gm[i])
+                .sum();
+            if self.gm[central] > 0.0 {
+                self.velocities[central] = -momentum / self.gm[central];
+            }
+        }
+
+        self.time = time;
+    }
+
+    /// Integra hasta `time`. Los saltos grandes (ir a una fecha) reinician el
+    /// estado desde Kepler en lugar de integrar años de golpe.
+    pub fn advance_to(&mut self, bodies: &[CelestialBody], time: f64) {
+        let delta = time - self.time;
+        if delta == 0.0 {
+            return;
+        }
+        if delta.abs() > RESEED_THRESHOLD {
+            self.reset(bodies, time);
+            return;
+        }
+
+        let substeps = ((delta.abs() / MAX_STEP).ceil() as usize).clamp(1, MAX_SUBSTEPS);
+        let step = delta / substeps as f64;
+        for _ in 0..substeps {
+            self.step(step);
+        }
+        self.time = time;
+    }
+
+    /// Multiplica la masa del cuerpo `index` (para él y para sus satélites).
+    pub fn scale_mass(&mut self, index: usize, factor: f64) {
+        self.gm[index] *= factor;
+        self.satellite_gm[index] *= factor;
+        self.mass_scale[index] *= factor;
+    }
+
+    /// Factor acumulado aplicado a la masa del cuerpo `index`.
+    pub fn mass_scale(&self, index: usize) -> f64 {
+        self.mass_scale[index]
+    }
+
+    /// Órbita osculante del cuerpo `index` respecto a su cuerpo central según
+    /// el estado integrado: refleja cómo la han alterado las demás masas.
+    pub fn osculating_orbit(&self, index: usize, time: f64) -> Option<OrbitalParameters> {
+        // Las lunas no arrastran a su planeta en la integración, así que solo
+        // en el nivel raíz se suma la masa propia (problema de dos cuerpos).
+        let (position, velocity, gm) = match self.parents[index] {
+            Some(parent) => (
+                self.positions[index],
+                self.velocities[index],
+                self.satellite_gm[parent],
+            ),
+            None => {
+                // En el nivel raíz el cuerpo central también se mueve.
+                let central = self.root_central()?;
+                if central == index {
+                    return None;
+                }
+                (
+                    self.positions[index] - self.positions[central],
+                    self.velocities[index] - self.velocities[central],
+                    self.gm[central] + self.gm[index],
+                )
+            }
+        };
+
+        OrbitalParameters::from_state_vectors(&position, &velocity, gm, time)
+    }
+
+    /// Cuerpo más masivo del nivel raíz (el Sol).
+    fn root_central(&self) -> Option<usize> {
+        (0..self.parents.len())
+            .filter(|&i| self.parents[i].is_none())
+            .max_by(|&a, &b| self.gm[a].total_cmp(&self.gm[b]))
+    }
+
+    /// Posiciones absolutas, con el mismo formato que las de los raíles de Kepler.
+    pub fn world_positions(&self, graph: &SceneGraph) -> Vec<DVec3> {
+        graph.compose(&self.positions)
+    }
+
+    /// Un paso de Yoshida: cuatro derivas y tres impulsos intercalados.
+    fn step(&mut self, dt: f64) {
+        for stage in 0..3 {
+            self.drift(YOSHIDA_DRIFT[stage] * dt);
+            self.compute_accelerations();
+            for (velocity, acceleration) in self.velocities.iter_mut().zip(&self.accelerations) {
+                *velocity += acceleration * (YOSHIDA_KICK[stage] * dt);
+            }
+        }
+        self.drift(YOSHIDA_DRIFT[3] * dt);
+    }
+
+    fn drift(&mut self, dt: f64) {
+        for (position, velocity) in self.positions.iter_mut().zip(&self.velocities) {
+            *position += velocity * dt;
+        }
+    }
+
+    fn compute_accelerations(&mut self) {
+        let count = self.positions.len();
+        for i in 0..count {
+            let mut acceleration = DVec3::zeros();
+            let position = self.positions[i];
+
+            // Cuerpo central del marco (el planeta, para una luna).
+            if let Some(parent) = self.parents[i] {
+                acceleration +=
+                    inverse_square(&-position, self.satellite_gm[parent], self.radii[parent]);
+            }
+
+            for j in 0..count {
+                if j != i && self.parents[j] == self.parents[i] {
+                    let offset = self.positions[j] - position;
+                    acceleration += inverse_square(&offset, self.gm[j], self.radii[j]);
+                }
+            }
+
+            self.accelerations[i] = acceleration;
+        }
+    }
+}
+
+/// Aceleración hacia una masa `gm` situada en `offset` respecto al cuerpo.
+/// Dentro de `radius` se trata como una esfera uniforme (fuerza lineal con la
+/// distancia), así el paso por el centro no diverge.
+fn inverse_square(offset: &DVec3, gm: f64, radius: f64) -> DVec3 {
+    let distance = offset.magnitude().max(radius);
+    if distance < 1e-6 || gm == 0.0 {
+        return DVec3::zeros();
+    }
+    offset * (gm / (distance * distance * distance))
+}
@@ -0,0 +1,490 @@
+use crate::celestial_body::CelestialBody;
+use crate::epoch::CalendarDate;
+use crate::flight::to_meters_per_second;
+use crate::framebuffer::{Color, Framebuffer};
+use crate::transfer_planner::{next_window, transfer_orbits, HohmannTransfer, LambertTransfer};
+
+/// Resolución de la malla de fechas (columnas de salida × filas de llegada).
+const COLUMNS: usize = 170;
+const ROWS: usize = 140;
+/// Las salidas cubren la ventana de Hohmann ± medio vuelo (o ± un cuarto del
+/// período sinódico, si es menor). Las llegadas van de medio vuelo después
+/// de la primera salida a 1.5 vuelos después de la última; los vuelos más
+/// cortos que 0.05 no se resuelven.
+const DEPARTURE_FLIGHT_FRACTION: f64 = 0.5;
+const DEPARTURE_SYNODIC_FRACTION: f64 = 0.25;
+const MIN_FLIGHT_FRACTION: f64 = 0.5;
+const MAX_FLIGHT_FRACTION: f64 = 1.5;
+const SHORTEST_FLIGHT_FRACTION: f64 = 0.05;
+
+/// Dimensiones de la imagen y del área del gráfico (píxeles).
+const IMAGE_WIDTH: usize = 960;
+const IMAGE_HEIGHT: usize = 720;
+const PLOT_LEFT: usize = 120;
+const PLOT_TOP: usize = 60;
+const PLOT_WIDTH: usize = 680;
+const PLOT_HEIGHT: usize = 560;
+const COLORBAR_LEFT: usize = 830;
+const COLORBAR_WIDTH: usize = 24;
+/// Escala de la fuente de 3×5 píxeles.
+const TEXT_SCALE: usize = 2;
+
+const BACKGROUND: Color = Color { r: 18, g: 18, b: 24 };
+/// Celdas sin solución o con C3 por encima de la escala.
+const NO_DATA: Color = Color { r: 40, g: 40, b: 48 };
+const FOREGROUND: Color = Color { r: 230, g: 230, b: 230 };
+
+/// Mapa de color viridis, de oscuro a claro.
+const VIRIDIS: [(f32, f32, f32); 9] = [
+    (68.0, 1.0, 84.0),
+    (71.0, 44.0, 122.0),
+    (59.0, 81.0, 139.0),
+    (44.0, 113.0, 142.0),
+    (33.0, 144.0, 141.0),
+    (39.0, 173.0, 129.0),
+    (92.0, 200.0, 99.0),
+    (170.0, 220.0, 50.0),
+    (253.0, 231.0, 37.0),
+];
+
+/// Coste de una transferencia de la malla (km²/s² y km/s).
+#[derive(Clone, Copy)]
+pub struct PorkchopCell {
+    /// Energía característica de salida: cuadrado del exceso de velocidad.
+    pub c3: f64,
+    /// Suma de los excesos de velocidad de salida y llegada.
+    pub delta_v: f64,
+}
+
+/// Diagrama "porkchop": coste de las transferencias de Lambert entre dos
+/// cuerpos para cada par de fechas de salida y llegada.
+///
+/// Las fechas de salida se centran en la próxima ventana de lanzamiento
+/// según el ángulo de fase de Hohmann.
+pub struct Porkchop {
+    pub origin: String,
+    pub target: String,
+    /// Primera y última salida (días desde J2000).
+    pub departure_range: (f64, f64),
+    /// Primera y última llegada (días desde J2000).
+    pub arrival_range: (f64, f64),
+    /// Celdas por filas de llegada; `None` si Lambert no tiene solución.
+    cells: Vec<Option<PorkchopCell>>,
+}
+
+impl Porkchop {
+    /// Resuelve Lambert en toda la malla de fechas alrededor de la primera
+    /// ventana desde `time`.
+    pub fn compute(bodies: &[CelestialBody], origin: usize, target: usize, time: f64) -> Result<Self, String> {
+        let (origin_orbit, target_orbit, gm) = transfer_orbits(bodies, origin, target)?;
+        let hohmann = HohmannTransfer::between(origin_orbit, target_orbit, gm, time);
+        let window = next_window(origin_orbit, target_orbit, &hohmann, time);
+
+        let margin = (DEPARTURE_FLIGHT_FRACTION * hohmann.time_of_flight)
+            .min(DEPARTURE_SYNODIC_FRACTION * hohmann.synodic_period);
+        let departure_range = (window - margin, window + margin);
+        let arrival_range = (
+            departure_range.0 + MIN_FLIGHT_FRACTION * hohmann.time_of_flight,
+            departure_range.1 + MAX_FLIGHT_FRACTION * hohmann.time_of_flight,
+        );
+        let shortest_flight = SHORTEST_FLIGHT_FRACTION * hohmann.time_of_flight;
+        let km_s = |speed: f64| to_meters_per_second(speed) / 1000.0;
+
+        let mut cells = Vec::with_capacity(COLUMNS * ROWS);
+        for row in 0..ROWS {
+            let arrival = lerp_range(arrival_range, row as f64 / (ROWS - 1) as f64);
+            for column in 0..COLUMNS {
+                let departure = lerp_range(departure_range, column as f64 / (COLUMNS - 1) as f64);
+                let time_of_flight = arrival - departure;
+                let cell = (time_of_flight > shortest_flight)
+                    .then(|| LambertTransfer::new(origin_orbit, target_orbit, gm, departure, time_of_flight))
+                    .flatten()
+                    .map(|transfer| PorkchopCell {
+                        c3: km_s(transfer.departure_excess).powi(2),
+                        delta_v: km_s(transfer.total_delta_v()),
+                    });
+                cells.push(cell);
+            }
+        }
+
+        Ok(Self {
+            origin: bodies[origin].name.clone(),
+            target: bodies[target].name.clone(),
+            departure_range,
+            arrival_range,
+            cells,
+        })
+    }
+
+    /// Celda de menor coste según `cost`, con sus fechas de salida y llegada.
+    pub fn minimum_by(&self, cost: impl Fn(&PorkchopCell) -> f64) -> Option<(f64, f64, PorkchopCell)> {
+        let (index, cell) = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, cell)| cell.map(|cell| (i, cell)))
+            .min_by(|(_, a), (_, b)| cost(a).total_cmp(&cost(b)))?;
+        Some((self.departure_at(index % COLUMNS), self.arrival_at(index / COLUMNS), cell))
+    }
+
+    fn departure_at(&self, column: usize) -> f64 {
+        lerp_range(self.departure_range, column as f64 / (COLUMNS - 1) as f64)
+    }
+
+    fn arrival_at(&self, row: usize) -> f64 {
+        lerp_range(self.arrival_range, row as f64 / (ROWS - 1) as f64)
+    }
+
+    /// Valor interpolado (bilineal) de la malla en coordenadas de celda
+    /// fraccionarias; `None` si falta alguna de las cuatro celdas vecinas.
+    fn sample(&self, column: f64, row: f64, value: impl Fn(&PorkchopCell) -> f64) -> Option<f64> {
+        let c0 = (column.floor() as usize).min(COLUMNS - 2);
+        let r0 = (row.floor() as usize).min(ROWS - 2);
+        let (tx, ty) = (column - c0 as f64, row - r0 as f64);
+        let at = |c: usize, r: usize| self.cells[r * COLUMNS + c].as_ref().map(&value);
+
+        let top = at(c0, r0)? * (1.0 - tx) + at(c0 + 1, r0)? * tx;
+        let bottom = at(c0, r0 + 1)? * (1.0 - tx) + at(c0 + 1, r0 + 1)? * tx;
+        Some(top * (1.0 - ty) + bottom * ty)
+    }
+
+    /// Dibuja el diagrama: C3 en color (claro = barato), curvas de nivel
+    /// del Δv total en blanco, ejes con fechas y barra de color.
+    pub fn render(&self) -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(IMAGE_WIDTH, IMAGE_HEIGHT);
+        framebuffer.clear(BACKGROUND);
+
+        let Some((_, _, best_c3)) = self.minimum_by(|cell| cell.c3) else {
+            draw_text(&mut framebuffer, PLOT_LEFT, PLOT_TOP, "SIN TRANSFERENCIAS", FOREGROUND);
+            return framebuffer;
+        };
+        let (_, _, best_delta_v) = self.minimum_by(|cell| cell.delta_v).unwrap_or((0.0, 0.0, best_c3));
+
+        // Escala de C3 a partir del mínimo: lo que queda por encima del
+        // cuádruple no es una ventana útil y se pinta como sin datos.
+        let c3_min = best_c3.c3;
+        let c3_max = (c3_min * 4.0).max(c3_min + 20.0);
+        let contour_step = nice_step(best_delta_v.delta_v / 6.0);
+        let contour_limit = best_delta_v.delta_v * 3.0;
+
+        // Banda de Δv de cada píxel del gráfico, para trazar las curvas de nivel.
+        let mut bands = vec![None; PLOT_WIDTH * PLOT_HEIGHT];
+        for py in 0..PLOT_HEIGHT {
+            // Las llegadas crecen hacia arriba.
+            let row = (PLOT_HEIGHT - 1 - py) as f64 / (PLOT_HEIGHT - 1) as f64 * (ROWS - 1) as f64;
+            for px in 0..PLOT_WIDTH {
+                let column = px as f64 / (PLOT_WIDTH - 1) as f64 * (COLUMNS - 1) as f64;
+                let color = match self.sample(column, row, |cell| cell.c3) {
+                    Some(c3) if c3 <= c3_max => colormap(1.0 - (c3 - c3_min) / (c3_max - c3_min)),
+                    _ => NO_DATA,
+                };
+                put(&mut framebuffer, PLOT_LEFT + px, PLOT_TOP + py, color);
+
+                bands[py * PLOT_WIDTH + px] = self
+                    .sample(column, row, |cell| cell.delta_v)
+                    .filter(|&delta_v| delta_v <= contour_limit)
+                    .map(|delta_v| (delta_v / contour_step).floor() as i64);
+            }
+        }
+
+        // Un píxel es de contorno si su banda cambia respecto al vecino
+        // derecho o inferior.
+        for py in 0..PLOT_HEIGHT - 1 {
+            for px in 0..PLOT_WIDTH - 1 {
+                let band = bands[py * PLOT_WIDTH + px];
+                let right = bands[py * PLOT_WIDTH + px + 1];
+                let below = bands[(py + 1) * PLOT_WIDTH + px];
+                let crosses = |other: Option<i64>| band.zip(other).is_some_and(|(a, b)| a != b);
+                if crosses(right) || crosses(below) {
+                    put(&mut framebuffer, PLOT_LEFT + px, PLOT_TOP + py, FOREGROUND);
+                }
+            }
+        }
+
+        if let Some((departure, arrival, _)) = self.minimum_by(|cell| cell.c3) {
+            let x = self.departure_to_x(departure);
+            let y = self.arrival_to_y(arrival);
+            for offset in 0..=12 {
+                put(&mut framebuffer, x + offset - 6, y, Color::new(255, 60, 60));
+                put(&mut framebuffer, x, y + offset - 6, Color::new(255, 60, 60));
+            }
+        }
+
+        self.draw_axes(&mut framebuffer);
+        draw_colorbar(&mut framebuffer, c3_min, c3_max);
+
+        let title = format!(
+            "{} > {}   C3 (KM2/S2) Y DV TOTAL (KM/S) CADA {}",
+            self.origin,
+            self.target,
+            format_value(contour_step)
+        );
+        draw_text(&mut framebuffer, PLOT_LEFT, 24, &title, FOREGROUND);
+        framebuffer
+    }
+
+    /// Dibuja el diagrama y lo guarda como PNG; devuelve un resumen con las
+    /// transferencias más baratas.
+    pub fn export(&self, path: &str) -> Result<String, String> {
+        self.render()
+            .save_png(path)
+            .map_err(|e| format!("No se pudo guardar {}: {}", path, e))?;
+
+        let describe = |label: &str, best: Option<(f64, f64, PorkchopCell)>| match best {
+            Some((departure, arrival, cell)) => format!(
+                "{}: salida {} → llegada {} ({:.0} días), C3 {:.1} km²/s², Δv {:.2} km/s",
+                label,
+                format_date(departure),
+                format_date(arrival),
+                arrival - departure,
+                cell.c3,
+                cell.delta_v
+            ),
+            None => format!("{}: sin solución", label),
+        };
+        Ok(format!(
+            "{} → {}\n  {}\n  {}",
+            self.origin,
+            self.target,
+            describe("Mínimo C3", self.minimum_by(|cell| cell.c3)),
+            describe("Mínimo Δv", self.minimum_by(|cell| cell.delta_v))
+        ))
+    }
+
+    fn departure_to_x(&self, departure: f64) -> usize {
+        let (start, end) = self.departure_range;
+        PLOT_LEFT + ((departure - start) / (end - start) * (PLOT_WIDTH - 1) as f64).round() as usize
+    }
+
+    fn arrival_to_y(&self, arrival: f64) -> usize {
+        let (start, end) = self.arrival_range;
+        PLOT_TOP + PLOT_HEIGHT
+            - 1
+            - ((arrival - start) / (end - start) * (PLOT_HEIGHT - 1) as f64).round() as usize
+    }
+
+    /// Marco del gráfico, marcas con fechas y nombres de los ejes.
+    fn draw_axes(&self, framebuffer: &mut Framebuffer) {
+        for x in PLOT_LEFT - 1..=PLOT_LEFT + PLOT_WIDTH {
+            put(framebuffer, x, PLOT_TOP - 1, FOREGROUND);
+            put(framebuffer, x, PLOT_TOP + PLOT_HEIGHT, FOREGROUND);
+        }
+        for y in PLOT_TOP - 1..=PLOT_TOP + PLOT_HEIGHT {
+            put(framebuffer, PLOT_LEFT - 1, y, FOREGROUND);
+            put(framebuffer, PLOT_LEFT + PLOT_WIDTH, y, FOREGROUND);
+        }
+
+        let label_width = text_width("0000-00-00");
+        for day in date_ticks(self.departure_range, PLOT_WIDTH / (label_width + 40)) {
+            let x = self.departure_to_x(day);
+            for y in PLOT_TOP + PLOT_HEIGHT..PLOT_TOP + PLOT_HEIGHT + 6 {
+                put(framebuffer, x, y, FOREGROUND);
+            }
+            let label_x = x.saturating_sub(label_width / 2);
+            if label_x + label_width > COLORBAR_LEFT {
+                continue;
+            }
+            draw_text(framebuffer, label_x, PLOT_TOP + PLOT_HEIGHT + 10, &format_date(day), FOREGROUND);
+        }
+        for day in date_ticks(self.arrival_range, PLOT_HEIGHT / 60) {
+            let y = self.arrival_to_y(day);
+            for x in PLOT_LEFT - 6..PLOT_LEFT {
+                put(framebuffer, x, y, FOREGROUND);
+            }
+            let label_x = PLOT_LEFT - 10 - label_width;
+            draw_text(framebuffer, label_x, y - 5 * TEXT_SCALE / 2, &format_date(day), FOREGROUND);
+        }
+
+        let departure_label = "SALIDA";
+        let label_x = PLOT_LEFT + (PLOT_WIDTH - text_width(departure_label)) / 2;
+        draw_text(framebuffer, label_x, PLOT_TOP + PLOT_HEIGHT + 32, departure_label, FOREGROUND);
+        draw_text(framebuffer, PLOT_LEFT - 10 - label_width, PLOT_TOP - 20, "LLEGADA", FOREGROUND);
+    }
+}
+
+/// Barra vertical con la escala de C3 y sus valores en los extremos y el centro.
+fn draw_colorbar(framebuffer: &mut Framebuffer, c3_min: f64, c3_max: f64) {
+    for py in 0..PLOT_HEIGHT {
+        let t = 1.0 - py as f64 / (PLOT_HEIGHT - 1) as f64;
+        let color = colormap(1.0 - t);
+        for px in 0..COLORBAR_WIDTH {
+            put(framebuffer, COLORBAR_LEFT + px, PLOT_TOP + py, color);
+        }
+    }
+
+    let label_x = COLORBAR_LEFT + COLORBAR_WIDTH + 8;
+    for (t, value) in [(0.0, c3_min), (0.5, (c3_min + c3_max) / 2.0), (1.0, c3_max)] {
+        let y = PLOT_TOP + ((1.0 - t) * (PLOT_HEIGHT - 1) as f64) as usize;
+        draw_text(framebuffer, label_x, y.saturating_sub(5), &format_value(value), FOREGROUND);
+    }
+    draw_text(framebuffer, COLORBAR_LEFT, PLOT_TOP - 20, "C3", FOREGROUND);
+}
+
+fn put(framebuffer: &mut Framebuffer, x: usize, y: usize, color: Color) {
+    framebuffer.blend_pixel(x, y, color, 1.0, 0.0);
+}
+
+fn lerp_range((start, end): (f64, f64), t: f64) -> f64 {
+    start + (end - start) * t
+}
+
+/// Color de viridis para `t` en [0, 1] (0 = oscuro, 1 = amarillo).
+fn colormap(t: f64) -> Color {
+    let position = t.clamp(0.0, 1.0) as f32 * (VIRIDIS.len() - 1) as f32;
+    let index = (position as usize).min(VIRIDIS.len() - 2);
+    let f = position - index as f32;
+    let (a, b) = (VIRIDIS[index], VIRIDIS[index + 1]);
+    Color::new(
+        (a.0 + (b.0 - a.0) * f) as u8,
+        (a.1 + (b.1 - a.1) * f) as u8,
+        (a.2 + (b.2 - a.2) * f) as u8,
+    )
+}
+
+/// Paso "redondo" (1, 2 o 5 × 10^k) más cercano por encima de `raw`.
+fn nice_step(raw: f64) -> f64 {
+    if raw <= 0.0 || !raw.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .find(|&m| m * magnitude >= raw)
+        .unwrap_or(10.0);
+    step * magnitude
+}
+
+/// Fechas de las marcas de un eje: el día 1 de cada mes múltiplo de un
+/// intervalo redondo de meses, como mucho `max_ticks`.
+fn date_ticks((start, end): (f64, f64), max_ticks: usize) -> Vec<f64> {
+    let span_months = (end - start) / 30.44;
+    let interval = [1, 2, 3, 6, 12, 24, 60]
+        .into_iter()
+        .find(|&months| span_months / months as f64 <= max_ticks.max(1) as f64)
+        .unwrap_or(120);
+
+    let first = CalendarDate::from_simulation_time(start);
+    let mut month_index = first.year * 12 + first.month as i32 - 1;
+    month_index += (interval - month_index.rem_euclid(interval)) % interval;
+
+    let mut ticks = Vec::new();
+    loop {
+        let date = CalendarDate {
+            year: month_index.div_euclid(12),
+            month: month_index.rem_euclid(12) as u32 + 1,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        };
+        let day = date.to_simulation_time();
+        if day > end {
+            break;
+        }
+        if day >= start {
+            ticks.push(day);
+        }
+        month_index += interval;
+    }
+    ticks
+}
+
+fn format_date(simulation_time: f64) -> String {
+    let date = CalendarDate::from_simulation_time(simulation_time);
+    format!("{:04}-{:02}-{:02}", date.year, date.month, date.day)
+}
+
+fn format_value(value: f64) -> String {
+    if value >= 100.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+// =================== TEXTO ===================
+
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 4 * TEXT_SCALE
+}
+
+/// Escribe `text` con la fuente de 3×5 píxeles, en mayúsculas y sin tildes.
+fn draw_text(framebuffer: &mut Framebuffer, x: usize, y: usize, text: &str, color: Color) {
+    for (i, c) in text.chars().enumerate() {
+        let rows = glyph(c);
+        let origin_x = x + i * 4 * TEXT_SCALE;
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                for sy in 0..TEXT_SCALE {
+                    for sx in 0..TEXT_SCALE {
+                        let px = origin_x + column * TEXT_SCALE + sx;
+                        put(framebuffer, px, y + row * TEXT_SCALE + sy, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Filas (3 bits, el más alto a la izquierda) de cada carácter. Los que no
+/// están definidos se dejan en blanco.
+fn glyph(c: char) -> [u8; 5] {
+    let c = match c.to_ascii_uppercase() {
+        'á' | 'Á' => 'A',
+        'é' | 'É' => 'E',
+        'í' | 'Í' => 'I',
+        'ó' | 'Ó' => 'O',
+        'ú' | 'Ú' | 'ü' | 'Ü' => 'U',
+        'ñ' | 'Ñ' => 'N',
+        other => other,
+    };
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '>' => [0b100, 0b010, 0b001, 0b010, 0b100],
+        '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+        _ => [0; 5],
+    }
+}
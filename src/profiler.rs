@@ -0,0 +1,268 @@
+use crate::renderer::RenderStats;
+use raylib::prelude::*;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+
+type RaylibColor = raylib::color::Color;
+
+/// Etapas del cuadro cuyo tiempo de reloj se mide por separado.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    /// Cálculo de posiciones orbitales (cuerpos y cinturón).
+    OrbitUpdate,
+    /// Fondo estelar.
+    Skybox,
+    /// Líneas de órbita.
+    Orbits,
+    /// Planetas, lunas, anillos y asteroides.
+    Bodies,
+    /// Nave en tercera persona.
+    Ship,
+    /// Estela de la nave.
+    Trail,
+    /// Post-proceso (modos de depuración y efecto warp).
+    Post,
+    /// Subida del framebuffer a la textura de raylib.
+    TextureUpload,
+}
+
+impl Stage {
+    /// Todas las etapas, en el orden en que se dibujan las gráficas.
+    pub const ALL: [Stage; 8] = [
+        Stage::OrbitUpdate,
+        Stage::Skybox,
+        Stage::Orbits,
+        Stage::Bodies,
+        Stage::Ship,
+        Stage::Trail,
+        Stage::Post,
+        Stage::TextureUpload,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Stage::OrbitUpdate => "Orbitas (calc)",
+            Stage::Skybox => "Skybox",
+            Stage::Orbits => "Orbitas (dibujo)",
+            Stage::Bodies => "Cuerpos",
+            Stage::Ship => "Nave",
+            Stage::Trail => "Trail",
+            Stage::Post => "Post",
+            Stage::TextureUpload => "Textura",
+        }
+    }
+
+    fn csv_name(self) -> &'static str {
+        match self {
+            Stage::OrbitUpdate => "orbit_update_ms",
+            Stage::Skybox => "skybox_ms",
+            Stage::Orbits => "orbits_ms",
+            Stage::Bodies => "bodies_ms",
+            Stage::Ship => "ship_ms",
+            Stage::Trail => "trail_ms",
+            Stage::Post => "post_ms",
+            Stage::TextureUpload => "texture_upload_ms",
+        }
+    }
+
+    fn color(self) -> RaylibColor {
+        match self {
+            Stage::OrbitUpdate => RaylibColor::new(120, 200, 255, 255),
+            Stage::Skybox => RaylibColor::new(150, 150, 170, 255),
+            Stage::Orbits => RaylibColor::new(100, 120, 220, 255),
+            Stage::Bodies => RaylibColor::new(255, 170, 60, 255),
+            Stage::Ship => RaylibColor::new(230, 110, 80, 255),
+            Stage::Trail => RaylibColor::new(80, 220, 120, 255),
+            Stage::Post => RaylibColor::new(220, 90, 220, 255),
+            Stage::TextureUpload => RaylibColor::new(240, 240, 90, 255),
+        }
+    }
+}
+
+/// Muestra de un cuadro: tiempos por etapa (ms) y contadores del renderer.
+#[derive(Clone, Copy)]
+struct FrameSample {
+    stage_ms: [f32; Stage::ALL.len()],
+    frame_ms: f32,
+    stats: RenderStats,
+}
+
+/// Perfilador de cuadros con historial circular y overlay en pantalla.
+///
+/// Uso por cuadro: `start`/`stop` alrededor de cada etapa y `end_frame`
+/// al final con los contadores del renderer.
+pub struct Profiler {
+    /// Indica si el overlay está visible.
+    pub visible: bool,
+    history: VecDeque<FrameSample>,
+    capacity: usize,
+    current: [f32; Stage::ALL.len()],
+    running: Option<(Stage, Instant)>,
+}
+
+impl Profiler {
+    /// Crea un perfilador que conserva los últimos `capacity` cuadros.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            visible: false,
+            history: VecDeque::with_capacity(capacity),
+            capacity,
+            current: [0.0; Stage::ALL.len()],
+            running: None,
+        }
+    }
+
+    /// Comienza a medir una etapa (cierra la anterior si seguía abierta).
+    pub fn start(&mut self, stage: Stage) {
+        self.stop();
+        self.running = Some((stage, Instant::now()));
+    }
+
+    /// Termina la medición de la etapa en curso y acumula su tiempo.
+    pub fn stop(&mut self) {
+        if let Some((stage, start)) = self.running.take() {
+            self.current[stage.index()] += start.elapsed().as_secs_f32() * 1000.0;
+        }
+    }
+
+    /// Cierra el cuadro actual y lo añade al historial.
+    pub fn end_frame(&mut self, frame_seconds: f32, stats: RenderStats) {
+        self.stop();
+
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(FrameSample {
+            stage_ms: self.current,
+            frame_ms: frame_seconds * 1000.0,
+            stats,
+        });
+        self.current = [0.0; Stage::ALL.len()];
+    }
+
+    /// Escribe el historial completo en un archivo CSV (una fila por cuadro).
+    pub fn dump_csv(&self, path: &str) -> std::io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+
+        let stage_columns: Vec<&str> = Stage::ALL.iter().map(|s| s.csv_name()).collect();
+        writeln!(
+            out,
+            "frame,frame_ms,{},meshes_submitted,meshes_culled,meshes_occluded,triangles_submitted,\
+             triangles_backface_culled,triangles_clipped,points_drawn,fragments_shaded,\
+             pixels_covered,overdraw",
+            stage_columns.join(",")
+        )?;
+
+        for (i, sample) in self.history.iter().enumerate() {
+            let stage_values: Vec<String> =
+                sample.stage_ms.iter().map(|ms| format!("{:.3}", ms)).collect();
+            let s = &sample.stats;
+            writeln!(
+                out,
+                "{},{:.3},{},{},{},{},{},{},{},{},{},{},{:.3}",
+                i,
+                sample.frame_ms,
+                stage_values.join(","),
+                s.meshes_submitted,
+                s.meshes_culled,
+                s.meshes_occluded,
+                s.triangles_submitted,
+                s.triangles_backface_culled,
+                s.triangles_clipped,
+                s.points_drawn,
+                s.fragments_shaded,
+                s.pixels_covered,
+                s.overdraw(),
+            )?;
+        }
+
+        out.flush()
+    }
+
+    /// Dibuja el overlay con contadores y gráficas apiladas por etapa.
+    pub fn draw(&self, d: &mut RaylibDrawHandle, x: i32, y: i32) {
+        if !self.visible {
+            return;
+        }
+
+        let Some(last) = self.history.back() else {
+            return;
+        };
+
+        let graph_height = 80;
+        let width = self.capacity as i32 + 170;
+        let height = 135 + graph_height.max(Stage::ALL.len() as i32 * 14);
+
+        d.draw_rectangle(x - 5, y - 5, width, height, RaylibColor::new(0, 0, 0, 200));
+        d.draw_text("PERFIL DE CUADRO", x, y, 16, RaylibColor::YELLOW);
+
+        let s = &last.stats;
+        let lines = [
+            format!("Cuadro: {:.2} ms", last.frame_ms),
+            format!(
+                "Mallas: {} enviadas, {} cull frustum, {} ocluidas",
+                s.meshes_submitted, s.meshes_culled, s.meshes_occluded
+            ),
+            format!(
+                "Triángulos: {} (back-face {}, recortados {})",
+                s.triangles_submitted, s.triangles_backface_culled, s.triangles_clipped
+            ),
+            format!("Puntos: {}  Fragmentos: {}", s.points_drawn, s.fragments_shaded),
+            format!("Píxeles: {}  Overdraw: {:.2}x", s.pixels_covered, s.overdraw()),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            d.draw_text(line, x, y + 22 + i as i32 * 16, 12, RaylibColor::WHITE);
+        }
+
+        // Gráfica apilada: una columna por cuadro, 1 px = 0.25 ms (tope en 20 ms).
+        let graph_top = y + 110;
+        let graph_bottom = graph_top + graph_height;
+        let px_per_ms = graph_height as f32 / 20.0;
+
+        d.draw_rectangle_lines(x - 1, graph_top - 1, self.capacity as i32 + 2, graph_height + 2, RaylibColor::DARKGRAY);
+        let target_y = graph_bottom - (1000.0 / 60.0 * px_per_ms) as i32;
+        d.draw_line(x, target_y, x + self.capacity as i32, target_y, RaylibColor::new(255, 80, 80, 140));
+
+        for (column, sample) in self.history.iter().enumerate() {
+            let column_x = x + column as i32;
+            let mut bottom = graph_bottom as f32;
+
+            for stage in Stage::ALL {
+                let h = sample.stage_ms[stage.index()] * px_per_ms;
+                let top = (bottom - h).max(graph_top as f32);
+                if top < bottom {
+                    d.draw_line(column_x, bottom as i32, column_x, top as i32, stage.color());
+                }
+                bottom = top;
+            }
+        }
+
+        // Leyenda con el tiempo de la última muestra.
+        let legend_x = x + self.capacity as i32 + 10;
+        for (i, stage) in Stage::ALL.iter().enumerate() {
+            let ly = graph_top + i as i32 * 14;
+            d.draw_rectangle(legend_x, ly + 2, 8, 8, stage.color());
+            d.draw_text(
+                &format!("{} {:.2}", stage.label(), last.stage_ms[stage.index()]),
+                legend_x + 12,
+                ly,
+                10,
+                RaylibColor::LIGHTGRAY,
+            );
+        }
+
+        d.draw_text(
+            "F4 ocultar | F5 exportar CSV",
+            x,
+            graph_bottom + 8,
+            10,
+            RaylibColor::GRAY,
+        );
+    }
+}
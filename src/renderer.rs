@@ -1,11 +1,117 @@
-use crate::framebuffer::{Framebuffer, Color};
+use crate::culling::{Frustum, OcclusionCuller};
+use crate::framebuffer::{log_depth, Framebuffer, Color};
 use crate::mesh::{ObjMesh, Vertex};
 use crate::shaders::PlanetShader;
 use nalgebra_glm::{Mat4, Vec2, Vec3, Vec4};
+use std::cell::Cell;
+
+/// Modos de visualización de depuración del renderer.
+///
+/// Se aplican dentro de las rutas normales de rasterizado (`render_mesh`,
+/// `render_instanced`, `render_ring`), así que muestran exactamente lo que
+/// el renderer está haciendo en cada cuadro.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DebugView {
+    /// Renderizado normal con los shaders de cada cuerpo.
+    None,
+    /// Valor guardado en el z-buffer logarítmico (blanco = cámara, negro =
+    /// `DEPTH_FAR`), el mismo que usa la prueba de profundidad.
+    Depth,
+    /// Normales en espacio mundial codificadas como RGB.
+    Normals,
+    /// Mapa de calor con la cantidad de fragmentos por píxel.
+    Overdraw,
+    /// Solo las aristas de los triángulos visibles.
+    Wireframe,
+    /// Dibuja las caras traseras en magenta en lugar de descartarlas.
+    Backfaces,
+    /// Tiñe cada malla según el nivel de detalle elegido.
+    LodLevel,
+}
+
+impl DebugView {
+    /// Devuelve el siguiente modo (para alternar con una sola tecla).
+    pub fn next(self) -> Self {
+        match self {
+            DebugView::None => DebugView::Depth,
+            DebugView::Depth => DebugView::Normals,
+            DebugView::Normals => DebugView::Overdraw,
+            DebugView::Overdraw => DebugView::Wireframe,
+            DebugView::Wireframe => DebugView::Backfaces,
+            DebugView::Backfaces => DebugView::LodLevel,
+            DebugView::LodLevel => DebugView::None,
+        }
+    }
+
+    /// Nombre corto del modo para mostrarlo en el HUD.
+    pub fn label(self) -> &'static str {
+        match self {
+            DebugView::None => "Normal",
+            DebugView::Depth => "Profundidad",
+            DebugView::Normals => "Normales",
+            DebugView::Overdraw => "Overdraw",
+            DebugView::Wireframe => "Wireframe",
+            DebugView::Backfaces => "Caras traseras",
+            DebugView::LodLevel => "Nivel LOD",
+        }
+    }
+}
+
+/// Tintes del modo `DebugView::LodLevel`, del nivel más detallado al más simple.
+const LOD_TINTS: [Vec3; 5] = [
+    Vec3::new(1.0, 0.25, 0.25),
+    Vec3::new(1.0, 0.6, 0.2),
+    Vec3::new(1.0, 1.0, 0.3),
+    Vec3::new(0.3, 1.0, 0.3),
+    Vec3::new(0.3, 0.5, 1.0),
+];
+
+/// Contadores del renderer acumulados durante un cuadro.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderStats {
+    /// Mallas enviadas a rasterizar (incluye cada instancia).
+    pub meshes_submitted: u32,
+    /// Objetos descartados por la prueba de frustum.
+    pub meshes_culled: u32,
+    /// Objetos descartados por quedar ocultos tras un cuerpo más cercano.
+    pub meshes_occluded: u32,
+    /// Triángulos procesados por las mallas enviadas.
+    pub triangles_submitted: u32,
+    /// Triángulos descartados por back-face culling.
+    pub triangles_backface_culled: u32,
+    /// Triángulos descartados por quedar fuera del rango de profundidad o de pantalla.
+    pub triangles_clipped: u32,
+    /// Impostores dibujados como puntos.
+    pub points_drawn: u32,
+    /// Fragmentos que llegaron a evaluar el shader.
+    pub fragments_shaded: u32,
+    /// Píxeles cubiertos por al menos un fragmento.
+    pub pixels_covered: u32,
+}
+
+impl RenderStats {
+    /// Promedio de fragmentos sombreados por píxel cubierto.
+    pub fn overdraw(&self) -> f32 {
+        if self.pixels_covered == 0 {
+            0.0
+        } else {
+            self.fragments_shaded as f32 / self.pixels_covered as f32
+        }
+    }
+}
 
 pub struct Renderer {
     pub width: f32,
     pub height: f32,
+    /// Modo de visualización de depuración activo.
+    pub debug_view: DebugView,
+    /// Grosor en píxeles de órbitas y estelas.
+    pub line_width: f32,
+    /// Nivel de detalle de la malla que se está dibujando (para `DebugView::LodLevel`).
+    lod_level: Option<usize>,
+    /// Contadores del cuadro actual. Se usa `Cell` porque las rutas de
+    /// renderizado solo reciben `&self`.
+    stats: Cell<RenderStats>,
 }
 
 impl Renderer {
@@ -13,13 +119,111 @@ impl Renderer {
         Renderer {
             width: width as f32,
             height: height as f32,
+            debug_view: DebugView::None,
+            line_width: 1.0,
+            lod_level: None,
+            stats: Cell::new(RenderStats::default()),
+        }
+    }
+
+    /// Actualiza las dimensiones del viewport (resolución interna del framebuffer).
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width as f32;
+        self.height = height as f32;
+    }
+
+    /// Reinicia los contadores; debe llamarse al comienzo de cada cuadro.
+    pub fn begin_frame(&self) {
+        self.stats.set(RenderStats::default());
+    }
+
+    /// Devuelve los contadores del cuadro, completando la cobertura de píxeles
+    /// a partir de los contadores de overdraw del framebuffer.
+    pub fn frame_stats(&self, framebuffer: &Framebuffer) -> RenderStats {
+        let mut stats = self.stats.get();
+        stats.pixels_covered = framebuffer.overdraw.iter().filter(|&&n| n > 0).count() as u32;
+        stats
+    }
+
+    #[inline]
+    fn count(&self, update: impl FnOnce(&mut RenderStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+
+    /// Indica el nivel de detalle de las próximas mallas (0 = máximo detalle).
+    ///
+    /// Solo afecta al modo `DebugView::LodLevel`; `None` deja la malla sin teñir.
+    pub fn set_lod_level(&mut self, level: Option<usize>) {
+        self.lod_level = level;
+    }
+
+    /// Aplica los modos de depuración que necesitan el cuadro completo.
+    ///
+    /// Debe llamarse después de dibujar la escena. Por ahora solo el mapa de
+    /// overdraw reemplaza el color final a partir de los contadores por píxel.
+    pub fn resolve_debug_view(&self, framebuffer: &mut Framebuffer) {
+        if self.debug_view != DebugView::Overdraw {
+            return;
+        }
+
+        for i in 0..framebuffer.width * framebuffer.height {
+            let heat = framebuffer.overdraw[i];
+            let color = match heat {
+                0 => Color::new(0, 0, 0),
+                1 => Color::new(20, 40, 160),
+                2 => Color::new(30, 160, 60),
+                3 => Color::new(220, 220, 40),
+                4 => Color::new(240, 130, 20),
+                _ => {
+                    let extra = ((heat - 5) as u32 * 12).min(255) as u8;
+                    Color::new(230, extra, extra)
+                }
+            };
+
+            let idx = i * 4;
+            framebuffer.buffer[idx] = color.r;
+            framebuffer.buffer[idx + 1] = color.g;
+            framebuffer.buffer[idx + 2] = color.b;
+        }
+    }
+
+    /// Color final de un fragmento según el modo de depuración activo.
+    fn shade_fragment(
+        &self,
+        shader: &dyn PlanetShader,
+        position: &Vec3,
+        normal: &Vec3,
+        view_depth: f32,
+        time: f32,
+    ) -> Color {
+        match self.debug_view {
+            DebugView::Depth => {
+                let gray = 1.0 - log_depth(view_depth).clamp(0.0, 1.0);
+                Color::from_vec3(Vec3::new(gray, gray, gray))
+            }
+            DebugView::Normals => Color::from_vec3(normal * 0.5 + Vec3::new(0.5, 0.5, 0.5)),
+            DebugView::LodLevel => {
+                let color = shader.fragment(position, normal, time);
+                match self.lod_level {
+                    Some(level) => {
+                        let tint = LOD_TINTS[level.min(LOD_TINTS.len() - 1)];
+                        let base = Vec3::new(color.r as f32, color.g as f32, color.b as f32) / 255.0;
+                        // Mezcla para que el tinte se vea incluso en zonas oscuras.
+                        Color::from_vec3(base.component_mul(&tint) * 0.5 + tint * 0.5)
+                    }
+                    None => color,
+                }
+            }
+            _ => shader.fragment(position, normal, time),
         }
     }
 
     fn is_valid_vertex(v: &TransformedVertex) -> bool {
         v.screen_pos.x.is_finite() 
             && v.screen_pos.y.is_finite()
-            && v.depth.is_finite()
+            && v.ndc_z.is_finite()
             && v.world_pos.x.is_finite()
             && v.world_pos.y.is_finite()
             && v.world_pos.z.is_finite()
@@ -36,11 +240,95 @@ impl Renderer {
         time: f32,
     ) {
         let mvp = projection_matrix * view_matrix * model_matrix;
+        self.draw_mesh(framebuffer, mesh, shader, model_matrix, &mvp, time);
+    }
+
+    /// Renderiza muchas copias de la misma malla, una por matriz modelo.
+    ///
+    /// La matriz vista-proyección se calcula una sola vez para todo el lote,
+    /// de modo que cada instancia solo paga su propia transformación de vértices.
+    pub fn render_instanced(
+        &self,
+        framebuffer: &mut Framebuffer,
+        mesh: &ObjMesh,
+        shader: &dyn PlanetShader,
+        instances: &[Mat4],
+        view_matrix: &Mat4,
+        projection_matrix: &Mat4,
+        time: f32,
+    ) {
+        let vp = projection_matrix * view_matrix;
+
+        for model_matrix in instances {
+            let mvp = vp * model_matrix;
+            self.draw_mesh(framebuffer, mesh, shader, model_matrix, &mvp, time);
+        }
+    }
+
+    /// Dibuja objetos lejanos como puntos (impostores) con z-test.
+    ///
+    /// Cada punto se describe como `(posición, tamaño en píxeles, color)`.
+    /// Es el nivel de detalle más barato: una proyección y unos pocos píxeles
+    /// por objeto, sin transformar ni rasterizar malla alguna.
+    pub fn render_points(
+        &self,
+        framebuffer: &mut Framebuffer,
+        points: &[(Vec3, f32, Color)],
+        view_matrix: &Mat4,
+        projection_matrix: &Mat4,
+    ) {
+        let vp = projection_matrix * view_matrix;
+
+        for (position, size, color) in points {
+            let clip_pos = vp * Vec4::new(position.x, position.y, position.z, 1.0);
+            let w = clip_pos.w;
+            if w <= 1e-6 {
+                continue;
+            }
+
+            let ndc = clip_pos.xyz() / w;
+            if ndc.z < -1.0 || ndc.z > 1.0 {
+                continue;
+            }
+
+            let depth = log_depth(w);
+            let screen_x = (ndc.x + 1.0) * 0.5 * self.width;
+            let screen_y = (1.0 - ndc.y) * 0.5 * self.height;
+            let extent = size.max(1.0).round() as i32;
+            self.count(|s| s.points_drawn += 1);
+            let start_x = (screen_x - extent as f32 * 0.5).floor() as i32;
+            let start_y = (screen_y - extent as f32 * 0.5).floor() as i32;
+
+            for dy in 0..extent {
+                for dx in 0..extent {
+                    let x = start_x + dx;
+                    let y = start_y + dy;
+                    if x >= 0 && y >= 0 {
+                        framebuffer.set_pixel(x as usize, y as usize, *color, depth);
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_mesh(
+        &self,
+        framebuffer: &mut Framebuffer,
+        mesh: &ObjMesh,
+        shader: &dyn PlanetShader,
+        model_matrix: &Mat4,
+        mvp: &Mat4,
+        time: f32,
+    ) {
+        self.count(|s| {
+            s.meshes_submitted += 1;
+            s.triangles_submitted += (mesh.indices.len() / 3) as u32;
+        });
 
         let transformed_vertices: Vec<_> = mesh
             .vertices
             .iter()
-            .map(|v| self.transform_vertex(v, model_matrix, &mvp))
+            .map(|v| self.transform_vertex(v, model_matrix, mvp))
             .collect();
 
         for i in (0..mesh.indices.len()).step_by(3) {
@@ -77,79 +365,159 @@ impl Renderer {
             return;
         }
 
+        let clip_points =
+            Self::project_points(orbit_points, parent_position, view_matrix, projection_matrix);
+
+        for i in 0..clip_points.len() {
+            let next_i = (i + 1) % clip_points.len();
+            self.draw_clipped_segment(framebuffer, &clip_points[i], &clip_points[next_i], color);
+        }
+    }
+
+    /// Como [`render_orbit`](Self::render_orbit), pero sin unir el último
+    /// punto con el primero: arcos de órbitas abiertas.
+    pub fn render_path(
+        &self,
+        framebuffer: &mut Framebuffer,
+        points: &[Vec3],
+        parent_position: Vec3,
+        view_matrix: &Mat4,
+        projection_matrix: &Mat4,
+        color: Color,
+    ) {
+        let clip_points =
+            Self::project_points(points, parent_position, view_matrix, projection_matrix);
+
+        for segment in clip_points.windows(2) {
+            self.draw_clipped_segment(framebuffer, &segment[0], &segment[1], color);
+        }
+    }
+
+    /// Lleva puntos relativos a `parent_position` al espacio de recorte.
+    fn project_points(
+        points: &[Vec3],
+        parent_position: Vec3,
+        view_matrix: &Mat4,
+        projection_matrix: &Mat4,
+    ) -> Vec<Vec4> {
         let vp = projection_matrix * view_matrix;
 
-        let screen_points: Vec<Option<Vec2>> = orbit_points
+        points
             .iter()
             .map(|point| {
                 let world_pos = parent_position + *point;
-                self.project_point(&world_pos, &vp)
+                vp * Vec4::new(world_pos.x, world_pos.y, world_pos.z, 1.0)
             })
-            .collect();
-
-        for i in 0..screen_points.len() {
-            let next_i = (i + 1) % screen_points.len();
+            .collect()
+    }
 
-            if let (Some(p1), Some(p2)) = (&screen_points[i], &screen_points[next_i]) {
-                self.draw_line(framebuffer, p1, p2, color);
+    /// Recorta un segmento en espacio de recorte contra los seis planos del
+    /// frustum (Liang-Barsky homogéneo) y dibuja lo que quede visible.
+    ///
+    /// Recortar antes de dividir por `w` evita que un extremo detrás de la
+    /// cámara invierta el segmento o lo haga desaparecer entero.
+    fn draw_clipped_segment(&self, framebuffer: &mut Framebuffer, a: &Vec4, b: &Vec4, color: Color) {
+        let boundary = |p: &Vec4| {
+            [
+                p.w + p.x,
+                p.w - p.x,
+                p.w + p.y,
+                p.w - p.y,
+                p.w + p.z,
+                p.w - p.z,
+            ]
+        };
+        let da = boundary(a);
+        let db = boundary(b);
+
+        let mut t_enter = 0.0_f32;
+        let mut t_exit = 1.0_f32;
+        for (d0, d1) in da.iter().zip(db.iter()) {
+            if *d0 < 0.0 && *d1 < 0.0 {
+                return;
+            }
+            if *d0 < 0.0 {
+                t_enter = t_enter.max(d0 / (d0 - d1));
+            } else if *d1 < 0.0 {
+                t_exit = t_exit.min(d0 / (d0 - d1));
             }
         }
-    }
-
-    fn project_point(&self, point: &Vec3, vp: &Mat4) -> Option<Vec2> {
-        let pos4 = Vec4::new(point.x, point.y, point.z, 1.0);
-        let clip_pos = vp * pos4;
-
-        let w = clip_pos.w;
-        if w.abs() < 1e-6 || w < 0.0 {
-            return None;
+        if t_enter > t_exit {
+            return;
         }
 
-        let ndc = clip_pos.xyz() / w;
+        let start = a + (b - a) * t_enter;
+        let end = a + (b - a) * t_exit;
 
-        if ndc.z < -1.0 || ndc.z > 1.0 {
-            return None;
-        }
+        let to_screen = |clip: &Vec4| {
+            let ndc = clip.xyz() / clip.w;
+            Vec3::new(
+                (ndc.x + 1.0) * 0.5 * self.width,
+                (1.0 - ndc.y) * 0.5 * self.height,
+                1.0 / clip.w,
+            )
+        };
 
-        let screen_x = (ndc.x + 1.0) * 0.5 * self.width;
-        let screen_y = (1.0 - ndc.y) * 0.5 * self.height;
+        self.draw_line(framebuffer, &to_screen(&start), &to_screen(&end), color);
+    }
 
-        if screen_x >= 0.0 && screen_x < self.width && screen_y >= 0.0 && screen_y < self.height {
-            Some(Vec2::new(screen_x, screen_y))
+    /// Dibuja una línea en pantalla con antialiasing de Xiaolin Wu y grosor
+    /// `line_width`.
+    ///
+    /// `p1` y `p2` llevan `1/w` en `z`, que es afín en espacio de pantalla; la
+    /// profundidad logarítmica se obtiene por píxel a partir de su inverso.
+    /// Cada píxel pasa el z-test pero no escribe profundidad, para que las
+    /// líneas no tapen lo que se dibuje después.
+    fn draw_line(&self, framebuffer: &mut Framebuffer, p1: &Vec3, p2: &Vec3, color: Color) {
+        let steep = (p2.y - p1.y).abs() > (p2.x - p1.x).abs();
+
+        // Se trabaja siempre sobre el eje mayor: (major, minor).
+        let (mut a, mut b) = if steep {
+            (Vec3::new(p1.y, p1.x, p1.z), Vec3::new(p2.y, p2.x, p2.z))
         } else {
-            None
+            (*p1, *p2)
+        };
+        if a.x > b.x {
+            std::mem::swap(&mut a, &mut b);
         }
-    }
 
-    fn draw_line(&self, framebuffer: &mut Framebuffer, p1: &Vec2, p2: &Vec2, color: Color) {
-        let mut x0 = p1.x as i32;
-        let mut y0 = p1.y as i32;
-        let x1 = p2.x as i32;
-        let y1 = p2.y as i32;
-
-        let dx = (x1 - x0).abs();
-        let dy = -(y1 - y0).abs();
-        let sx = if x0 < x1 { 1 } else { -1 };
-        let sy = if y0 < y1 { 1 } else { -1 };
-        let mut err = dx + dy;
-
-        loop {
-            if x0 >= 0 && x0 < self.width as i32 && y0 >= 0 && y0 < self.height as i32 {
-                framebuffer.set_pixel(x0 as usize, y0 as usize, color, 0.0);
-            }
+        let length = b.x - a.x;
+        let gradient = if length > 1e-6 { (b.y - a.y) / length } else { 0.0 };
+        let inv_w_gradient = if length > 1e-6 { (b.z - a.z) / length } else { 0.0 };
 
-            if x0 == x1 && y0 == y1 {
-                break;
-            }
+        // Grosor medido sobre el eje menor para que las diagonales no adelgacen.
+        let half_width = self.line_width.max(1.0) * (1.0 + gradient * gradient).sqrt() * 0.5;
 
-            let e2 = 2 * err;
-            if e2 >= dy {
-                err += dy;
-                x0 += sx;
-            }
-            if e2 <= dx {
-                err += dx;
-                y0 += sy;
+        let (major_limit, minor_limit) = if steep {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+
+        // Rango semiabierto para no mezclar dos veces el vértice compartido
+        // entre segmentos consecutivos.
+        let first = a.x.round().max(0.0) as i32;
+        let last = b.x.round().min(major_limit) as i32;
+
+        for major in first..last {
+            let center = major as f32 + 0.5;
+            let minor_center = a.y + gradient * (center - a.x);
+            let depth = log_depth(1.0 / (a.z + inv_w_gradient * (center - a.x)));
+
+            let lowest = (minor_center - half_width - 0.5).floor().max(0.0) as i32;
+            let highest = (minor_center + half_width + 0.5)
+                .ceil()
+                .min(minor_limit - 1.0) as i32;
+
+            for minor in lowest..=highest {
+                let distance = (minor as f32 + 0.5 - minor_center).abs();
+                let coverage = (half_width + 0.5 - distance).clamp(0.0, 1.0);
+                if coverage <= 0.0 {
+                    continue;
+                }
+
+                let (x, y) = if steep { (minor, major) } else { (major, minor) };
+                framebuffer.blend_pixel(x as usize, y as usize, color, coverage, depth);
             }
         }
     }
@@ -172,7 +540,8 @@ impl Renderer {
         if w.abs() < 1e-6 {
             return TransformedVertex {
                 screen_pos: Vec2::new(-1000.0, -1000.0),
-                depth: 1.0,
+                ndc_z: 1.0,
+                inv_w: 0.0,
                 world_pos: world_pos.xyz(),
                 world_normal,
             };
@@ -186,7 +555,8 @@ impl Renderer {
 
         TransformedVertex {
             screen_pos: screen,
-            depth: ndc.z,
+            ndc_z: ndc.z,
+            inv_w: 1.0 / w,
             world_pos: world_pos.xyz(),
             world_normal,
         }
@@ -204,10 +574,11 @@ impl Renderer {
         if !Self::is_valid_vertex(v0) 
             || !Self::is_valid_vertex(v1) 
             || !Self::is_valid_vertex(v2) {
+            self.count(|s| s.triangles_clipped += 1);
             return;
         }
 
-        // Back-face culling
+        // Back-face culling (el modo de depuración de caras traseras las conserva)
         let edge1 = Vec2::new(
             v1.screen_pos.x - v0.screen_pos.x,
             v1.screen_pos.y - v0.screen_pos.y,
@@ -217,15 +588,27 @@ impl Renderer {
             v2.screen_pos.y - v0.screen_pos.y,
         );
         let cross = edge1.x * edge2.y - edge1.y * edge2.x;
+        let is_backface = cross <= 0.0;
         
-        if cross <= 0.0 {
+        if cross == 0.0 || (is_backface && self.debug_view != DebugView::Backfaces) {
+            self.count(|s| s.triangles_backface_culled += 1);
             return;
         }
 
+        // Longitud de cada arista opuesta a un vértice, para el modo wireframe:
+        // la distancia en píxeles a la arista i es w_i * |cross| / longitud_i.
+        let edge_lengths = [
+            (v2.screen_pos - v1.screen_pos).magnitude(),
+            (v2.screen_pos - v0.screen_pos).magnitude(),
+            (v1.screen_pos - v0.screen_pos).magnitude(),
+        ];
+        let wireframe = self.debug_view == DebugView::Wireframe;
+
         // ✅ MEJORADO: Validación más robusta de profundidad
-        if v0.depth < -1.0 || v0.depth > 1.0 ||
-            v1.depth < -1.0 || v1.depth > 1.0 ||
-            v2.depth < -1.0 || v2.depth > 1.0 {
+        if v0.ndc_z < -1.0 || v0.ndc_z > 1.0 ||
+            v1.ndc_z < -1.0 || v1.ndc_z > 1.0 ||
+            v2.ndc_z < -1.0 || v2.ndc_z > 1.0 {
+            self.count(|s| s.triangles_clipped += 1);
             return;
         }
 
@@ -239,15 +622,19 @@ impl Renderer {
             .ceil().min(self.height - 1.0) as usize;
 
         if min_x >= max_x || min_y >= max_y {
+            self.count(|s| s.triangles_clipped += 1);
             return;
         }
 
         let bbox_width = max_x - min_x;
         let bbox_height = max_y - min_y;
         if bbox_width > self.width as usize * 2 || bbox_height > self.height as usize * 2 {
+            self.count(|s| s.triangles_clipped += 1);
             return;
         }
 
+        let mut fragments_shaded = 0;
+
         for y in min_y..=max_y {
             for x in min_x..=max_x {
                 let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
@@ -260,11 +647,12 @@ impl Renderer {
                 );
 
                 if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
-                    // ✅ CORRECTO: Interpolar depth en NDC space
-                    let depth = w0 * v0.depth + w1 * v1.depth + w2 * v2.depth;
+                    // 1/w es afín en pantalla: se interpola y se invierte por fragmento.
+                    let view_depth = 1.0 / (w0 * v0.inv_w + w1 * v1.inv_w + w2 * v2.inv_w);
+                    let depth = log_depth(view_depth);
                     
                     // Validación final
-                    if !depth.is_finite() || depth < -1.0 || depth > 1.0 {
+                    if !depth.is_finite() || depth > 1.0 {
                         continue;
                     }
 
@@ -278,58 +666,73 @@ impl Renderer {
                         continue;
                     }
 
+                    let count = &mut framebuffer.overdraw[y * framebuffer.width + x];
+                    *count = count.saturating_add(1);
+
+                    if wireframe {
+                        let edge_distance = (w0 * cross.abs() / edge_lengths[0])
+                            .min(w1 * cross.abs() / edge_lengths[1])
+                            .min(w2 * cross.abs() / edge_lengths[2]);
+                        if edge_distance > 1.0 {
+                            continue;
+                        }
+                    }
+
                     let world_normal = (v0.world_normal * w0 
                         + v1.world_normal * w1 
                         + v2.world_normal * w2)
                         .normalize();
 
-                    let color = shader.fragment(&world_pos, &world_normal, time);
+                    let color = if is_backface {
+                        Color::new(255, 0, 255)
+                    } else if wireframe {
+                        Color::new(200, 220, 255)
+                    } else {
+                        self.shade_fragment(shader, &world_pos, &world_normal, view_depth, time)
+                    };
+                    fragments_shaded += 1;
                     
-                    // ✅ Pasar depth directamente (sin normalizar)
                     framebuffer.set_pixel(x, y, color, depth);
                 }
             }
         }
+
+        self.count(|s| s.fragments_shaded += fragments_shaded);
     }   
         
+    /// Prueba exacta de la esfera contra los seis planos del frustum.
+    ///
+    /// El frustum se extrae una vez por cuadro y se comparte entre todas las
+    /// pruebas de ese cuadro.
     pub fn is_in_frustum(
         &self,
+        frustum: &Frustum,
         object_position: &Vec3,
         object_radius: f32,
-        view_matrix: &Mat4,
-        projection_matrix: &Mat4,
     ) -> bool {
-        let vp = projection_matrix * view_matrix;
-        let pos4 = Vec4::new(object_position.x, object_position.y, object_position.z, 1.0);
-        let clip_pos = vp * pos4;
-
-        let view_pos = view_matrix * pos4;
-        
-        // CAMBIADO: Permitir objetos grandes detrás de la cámara
-        // si parte de ellos podría ser visible
-        if view_pos.z > object_radius * 2.0 {
-            return false;
+        let visible = frustum.intersects_sphere(object_position, object_radius);
+        if !visible {
+            self.count(|s| s.meshes_culled += 1);
         }
+        visible
+    }
 
-        let w = clip_pos.w;
-        // if w <= 0.0 && view_pos.z.abs() > object_radius {
-        if w <= 0.0 {
-            return false; // Solo cullear si está completamente detrás
+    /// Prueba de oclusión del cuerpo `index` contra los oclusores del cuadro.
+    pub fn is_occluded(
+        &self,
+        culler: &OcclusionCuller,
+        index: usize,
+        object_position: &Vec3,
+        object_radius: f32,
+    ) -> bool {
+        let occluded = culler.is_occluded(index, object_position, object_radius);
+        if occluded {
+            self.count(|s| s.meshes_occluded += 1);
         }
-
-        // Margen más generoso para objetos grandes
-        let screen_size = object_radius / w.abs();
-        let margin = (screen_size * 2.0).max(1.0).min(20.0);
-        
-        // Verificar si está dentro del frustum con margen
-        let x_test = clip_pos.x.abs() < w.abs() * (1.0 + margin);
-        let y_test = clip_pos.y.abs() < w.abs() * (1.0 + margin);
-        let z_test = clip_pos.z > -w.abs() * (1.0 + margin) 
-            && clip_pos.z < w.abs();
-        
-        x_test && y_test && z_test
+        occluded
     }
 
+
     pub fn render_line(
         &self,
         framebuffer: &mut Framebuffer,
@@ -340,155 +743,11 @@ impl Renderer {
         color: Color,
     ) {
         let vp = projection_matrix * view_matrix;
-        
-        if let (Some(p1), Some(p2)) = (
-            self.project_point(start, &vp),
-            self.project_point(end, &vp),
-        ) {
-            self.draw_line(framebuffer, &p1, &p2, color);
-        }
-    }
-        /// Renderiza sin z-test (siempre visible, como overlay)
-    pub fn render_mesh_overlay(
-        &self,
-        framebuffer: &mut Framebuffer,
-        mesh: &ObjMesh,
-        shader: &dyn PlanetShader,
-        model_matrix: &Mat4,
-        view_matrix: &Mat4,
-        projection_matrix: &Mat4,
-        time: f32,
-    ) {
-        let mvp = projection_matrix * view_matrix * model_matrix;
-
-        let transformed_vertices: Vec<_> = mesh
-            .vertices
-            .iter()
-            .map(|v| self.transform_vertex(v, model_matrix, &mvp))
-            .collect();
-
-        for i in (0..mesh.indices.len()).step_by(3) {
-            let i0 = mesh.indices[i] as usize;
-            let i1 = mesh.indices[i + 1] as usize;
-            let i2 = mesh.indices[i + 2] as usize;
-
-            if i0 < transformed_vertices.len()
-                && i1 < transformed_vertices.len()
-                && i2 < transformed_vertices.len()
-            {
-                self.rasterize_triangle_overlay(
-                    framebuffer,
-                    &transformed_vertices[i0],
-                    &transformed_vertices[i1],
-                    &transformed_vertices[i2],
-                    shader,
-                    time,
-                );
-            }
-        }
-    }
-
-    fn rasterize_triangle_overlay(
-        &self,
-        framebuffer: &mut Framebuffer,
-        v0: &TransformedVertex,
-        v1: &TransformedVertex,
-        v2: &TransformedVertex,
-        shader: &dyn PlanetShader,
-        time: f32,
-    ) {
-        if !Self::is_valid_vertex(v0) 
-            || !Self::is_valid_vertex(v1) 
-            || !Self::is_valid_vertex(v2) {
-            return;
-        }
-
-        // Back-face culling
-        let edge1 = Vec2::new(
-            v1.screen_pos.x - v0.screen_pos.x,
-            v1.screen_pos.y - v0.screen_pos.y,
-        );
-        let edge2 = Vec2::new(
-            v2.screen_pos.x - v0.screen_pos.x,
-            v2.screen_pos.y - v0.screen_pos.y,
-        );
-        let cross = edge1.x * edge2.y - edge1.y * edge2.x;
-        
-        if cross <= 0.0 {
-            return;
-        }
-
-        if v0.depth > 1.0 || v1.depth > 1.0 || v2.depth > 1.0 {
-            return;
-        }
-
-        let min_x = v0.screen_pos.x.min(v1.screen_pos.x).min(v2.screen_pos.x)
-            .floor().max(0.0) as usize;
-        let max_x = v0.screen_pos.x.max(v1.screen_pos.x).max(v2.screen_pos.x)
-            .ceil().min(self.width - 1.0) as usize;
-        let min_y = v0.screen_pos.y.min(v1.screen_pos.y).min(v2.screen_pos.y)
-            .floor().max(0.0) as usize;
-        let max_y = v0.screen_pos.y.max(v1.screen_pos.y).max(v2.screen_pos.y)
-            .ceil().min(self.height - 1.0) as usize;
-
-        if min_x >= max_x || min_y >= max_y {
-            return;
-        }
-
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
-
-                let (w0, w1, w2) = barycentric(
-                    &p,
-                    &v0.screen_pos,
-                    &v1.screen_pos,
-                    &v2.screen_pos
-                );
-
-                if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
-                    let _depth = w0 * v0.depth + w1 * v1.depth + w2 * v2.depth;
-                    
-                    let index = y * framebuffer.width + x;
-                    
-                    // Si hay algo muy cerca (depth muy bajo), no sobrescribir
-                    if framebuffer.zbuffer[index] < -0.9 {
-                        continue;
-                    }
-                    
-                    let world_pos = v0.world_pos * w0 
-                        + v1.world_pos * w1 
-                        + v2.world_pos * w2;
-                    
-                    if !world_pos.x.is_finite() 
-                        || !world_pos.y.is_finite() 
-                        || !world_pos.z.is_finite() {
-                        continue;
-                    }
-
-                    let world_normal = (v0.world_normal * w0 
-                        + v1.world_normal * w1 
-                        + v2.world_normal * w2)
-                        .normalize();
+        let clip_start = vp * Vec4::new(start.x, start.y, start.z, 1.0);
+        let clip_end = vp * Vec4::new(end.x, end.y, end.z, 1.0);
 
-                    let color = shader.fragment(&world_pos, &world_normal, time);
-                    
-                    let idx = index * 4;
-                    let alpha = 0.95; // 95% nave, 5% fondo
-                    
-                    framebuffer.buffer[idx] = (color.r as f32 * alpha 
-                        + framebuffer.buffer[idx] as f32 * (1.0 - alpha)) as u8;
-                    framebuffer.buffer[idx + 1] = (color.g as f32 * alpha 
-                        + framebuffer.buffer[idx + 1] as f32 * (1.0 - alpha)) as u8;
-                    framebuffer.buffer[idx + 2] = (color.b as f32 * alpha 
-                        + framebuffer.buffer[idx + 2] as f32 * (1.0 - alpha)) as u8;
-                    framebuffer.buffer[idx + 3] = 255;
-                    
-                }
-            }
-        }
+        self.draw_clipped_segment(framebuffer, &clip_start, &clip_end, color);
     }
-
     pub fn render_ring(
     &self,
     framebuffer: &mut Framebuffer,
@@ -500,6 +759,10 @@ impl Renderer {
     time: f32,
 ) {
     let mvp = projection_matrix * view_matrix * model_matrix;
+    self.count(|s| {
+        s.meshes_submitted += 1;
+        s.triangles_submitted += (mesh.indices.len() / 3) as u32;
+    });
 
     let transformed_vertices: Vec<_> = mesh
         .vertices
@@ -549,10 +812,16 @@ fn rasterize_triangle_with_position(
     if !Self::is_valid_vertex(tv0) 
         || !Self::is_valid_vertex(tv1) 
         || !Self::is_valid_vertex(tv2) {
+        self.count(|s| s.triangles_clipped += 1);
         return;
     }
 
-    // ... (código de culling y clipping igual) ...
+    if tv0.ndc_z < -1.0 || tv0.ndc_z > 1.0 ||
+        tv1.ndc_z < -1.0 || tv1.ndc_z > 1.0 ||
+        tv2.ndc_z < -1.0 || tv2.ndc_z > 1.0 {
+        self.count(|s| s.triangles_clipped += 1);
+        return;
+    }
     
     let edge1 = Vec2::new(
         tv1.screen_pos.x - tv0.screen_pos.x,
@@ -563,11 +832,21 @@ fn rasterize_triangle_with_position(
         tv2.screen_pos.y - tv0.screen_pos.y,
     );
     let cross = edge1.x * edge2.y - edge1.y * edge2.x;
+    let is_backface = cross <= 0.0;
     
-    if cross <= 0.0 {
+    // Mismo tratamiento de depuración que `rasterize_triangle`.
+    if cross == 0.0 || (is_backface && self.debug_view != DebugView::Backfaces) {
+        self.count(|s| s.triangles_backface_culled += 1);
         return;
     }
 
+    let edge_lengths = [
+        (tv2.screen_pos - tv1.screen_pos).magnitude(),
+        (tv2.screen_pos - tv0.screen_pos).magnitude(),
+        (tv1.screen_pos - tv0.screen_pos).magnitude(),
+    ];
+    let wireframe = self.debug_view == DebugView::Wireframe;
+
     let min_x = tv0.screen_pos.x.min(tv1.screen_pos.x).min(tv2.screen_pos.x)
         .floor().max(0.0) as usize;
     let max_x = tv0.screen_pos.x.max(tv1.screen_pos.x).max(tv2.screen_pos.x)
@@ -577,6 +856,7 @@ fn rasterize_triangle_with_position(
     let max_y = tv0.screen_pos.y.max(tv1.screen_pos.y).max(tv2.screen_pos.y)
         .ceil().min(self.height - 1.0) as usize;
 
+    let mut fragments_shaded = 0;
     for y in min_y..=max_y {
         for x in min_x..=max_x {
             let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
@@ -589,9 +869,10 @@ fn rasterize_triangle_with_position(
             );
 
             if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
-                let depth = w0 * tv0.depth + w1 * tv1.depth + w2 * tv2.depth;
+                let view_depth = 1.0 / (w0 * tv0.inv_w + w1 * tv1.inv_w + w2 * tv2.inv_w);
+                let depth = log_depth(view_depth);
                 
-                if !depth.is_finite() || depth < -1.0 || depth > 1.0 {
+                if !depth.is_finite() || depth > 1.0 {
                     continue;
                 }
 
@@ -603,19 +884,45 @@ fn rasterize_triangle_with_position(
                     + tv2.world_normal * w2)
                     .normalize();
 
+                let count = &mut framebuffer.overdraw[y * framebuffer.width + x];
+                *count = count.saturating_add(1);
+
+                if wireframe {
+                    let edge_distance = (w0 * cross.abs() / edge_lengths[0])
+                        .min(w1 * cross.abs() / edge_lengths[1])
+                        .min(w2 * cross.abs() / edge_lengths[2]);
+                    if edge_distance > 1.0 {
+                        continue;
+                    }
+                }
+
                 // Pasar posición real al shader
-                let color = shader.fragment(&model_pos, &world_normal, time);
+                let color = if is_backface {
+                    Color::new(255, 0, 255)
+                } else if wireframe {
+                    Color::new(200, 220, 255)
+                } else {
+                    self.shade_fragment(shader, &model_pos, &world_normal, view_depth, time)
+                };
+                fragments_shaded += 1;
                 
                 framebuffer.set_pixel(x, y, color, depth);
             }
         }
     }
+
+    self.count(|s| s.fragments_shaded += fragments_shaded);
 }
 }
 
 struct TransformedVertex {
     screen_pos: Vec2,
-    depth: f32,
+    /// Profundidad NDC; solo se usa para descartar vértices fuera de los planos
+    /// cercano y lejano. El z-test usa la profundidad logarítmica.
+    ndc_z: f32,
+    /// Inverso de la coordenada w de clip; se interpola linealmente en pantalla
+    /// para recuperar la profundidad de vista con corrección de perspectiva.
+    inv_w: f32,
     world_pos: Vec3,
     world_normal: Vec3,
 }
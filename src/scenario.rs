@@ -0,0 +1,536 @@
+use crate::celestial_body::*;
+use nalgebra_glm::Vec3;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+/// Asteroides del cinturón si el escenario no indica otra cosa.
+pub const DEFAULT_ASTEROID_COUNT: usize = 10000;
+/// Semilla de los generadores aleatorios si ni la línea de comandos ni el
+/// escenario indican otra.
+pub const DEFAULT_SEED: u64 = 2024;
+
+/// Sistema completo descrito en un archivo TOML.
+///
+/// Los cuerpos se listan como tablas `[[body]]` y se enlazan con su padre por
+/// nombre. Los ángulos (también los de la rotación) van en grados, las
+/// distancias en unidades de simulación (1 UA = 7480) y los tiempos en días.
+/// Ejemplo mínimo:
+///
+/// ```toml
+/// name = "Sistema de prueba"
+/// asteroid_count = 0
+/// seed = 42
+///
+/// [[body]]
+/// name = "Estrella"
+/// type = "star"
+/// radius = 300.0
+/// rotation_period = 25.0
+///
+/// [[body]]
+/// name = "Mundo"
+/// type = "planet"
+/// radius = 30.0
+/// rotation_period = 1.0
+/// shader = "earth"
+///
+/// [body.orbit]
+/// semi_major_axis = 7480.0
+/// period = 365.25
+/// ```
+#[derive(Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_asteroid_count")]
+    pub asteroid_count: usize,
+    /// Semilla del fondo estelar y de los cuerpos menores.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(rename = "body", default)]
+    pub bodies: Vec<BodyDefinition>,
+}
+
+/// Un cuerpo tal como aparece en el archivo.
+#[derive(Serialize, Deserialize)]
+pub struct BodyDefinition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub body_type: CelestialType,
+    /// Radio visual (unidades de simulación).
+    pub radius: f32,
+    /// Nombre del cuerpo padre; sin padre orbita el origen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Período de rotación sidéreo (días); negativo si el giro es retrógrado.
+    #[serde(default)]
+    pub rotation_period: f64,
+    /// Inclinación del eje respecto a la normal de la órbita.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub obliquity: f64,
+    /// Longitud hacia la que se inclina el eje, en el plano orbital.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub axis_longitude: f64,
+    /// Ángulo del meridiano origen en J2000.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub prime_meridian: f64,
+    /// Período de precesión del eje (días); negativo si es retrógrada.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub precession_period: f64,
+    /// Mantiene siempre la misma cara hacia el padre.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub tidally_locked: bool,
+    /// Formato anterior: eje de giro en coordenadas globales. Si aparece, se
+    /// convierte en oblicuidad y longitud del eje respecto a la eclíptica.
+    #[serde(default, skip_serializing)]
+    pub rotation_axis: Option<[f32; 3]>,
+    /// Shader de superficie; por defecto el del tipo de cuerpo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shader: Option<SurfaceShader>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orbit: Option<OrbitDefinition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rings: Option<RingsDefinition>,
+    #[serde(default)]
+    pub physical: PhysicalProperties,
+}
+
+/// Elementos orbitales con los ángulos en grados.
+///
+/// Las órbitas cerradas se dan con `semi_major_axis`, `period` y
+/// `mean_anomaly`; las abiertas (e ≥ 1) con `periapsis_distance`,
+/// `time_of_periapsis` y `gravitational_parameter`.
+#[derive(Serialize, Deserialize)]
+pub struct OrbitDefinition {
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub semi_major_axis: f64,
+    #[serde(default)]
+    pub eccentricity: f64,
+    #[serde(default)]
+    pub inclination: f64,
+    #[serde(default)]
+    pub longitude_of_ascending_node: f64,
+    #[serde(default)]
+    pub argument_of_periapsis: f64,
+    /// Período orbital (días).
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub period: f64,
+    /// Anomalía media en J2000.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub mean_anomaly: f64,
+    /// Distancia del periapsis de una órbita abierta.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub periapsis_distance: Option<f64>,
+    /// Paso por el periapsis de una órbita abierta (días desde J2000).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_of_periapsis: Option<f64>,
+    /// GM del cuerpo central para una órbita abierta (unidades³/día²).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gravitational_parameter: Option<f64>,
+    /// Deriva de los elementos por siglo juliano (mismas unidades).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secular_rates: Option<SecularRatesDefinition>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SecularRatesDefinition {
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub longitude_of_ascending_node: f64,
+    pub argument_of_periapsis: f64,
+}
+
+/// Anillos con radios en múltiplos del radio del cuerpo y la inclinación en grados.
+#[derive(Serialize, Deserialize)]
+pub struct RingsDefinition {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    #[serde(default)]
+    pub tilt: f32,
+    #[serde(default = "default_tilt_axis")]
+    pub tilt_axis: [f32; 3],
+}
+
+fn default_asteroid_count() -> usize {
+    DEFAULT_ASTEROID_COUNT
+}
+
+fn default_tilt_axis() -> [f32; 3] {
+    [1.0, 0.0, 0.0]
+}
+
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl Scenario {
+    /// Lee un escenario desde un archivo TOML.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        toml::from_str(&text).map_err(|e| format!("Escenario inválido en {}: {}", path, e))
+    }
+
+    /// Escribe el escenario en un archivo TOML.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let text = toml::to_string_pretty(self)
+            .map_err(|e| format!("No se pudo serializar el escenario: {}", e))?;
+        fs::write(path, text).map_err(|e| format!("No se pudo escribir {}: {}", path, e))
+    }
+
+    /// Describe una lista de cuerpos ya construida.
+    pub fn from_bodies(name: &str, bodies: &[CelestialBody], asteroid_count: usize, seed: u64) -> Self {
+        Self {
+            name: name.to_string(),
+            description: String::new(),
+            asteroid_count,
+            seed: Some(seed),
+            bodies: bodies
+                .iter()
+                .map(|body| BodyDefinition {
+                    name: body.name.clone(),
+                    body_type: body.body_type,
+                    radius: body.radius,
+                    parent: body.parent_index.map(|p| bodies[p].name.clone()),
+                    rotation_period: body.rotation.period,
+                    obliquity: body.rotation.obliquity.to_degrees(),
+                    axis_longitude: body.rotation.axis_longitude.to_degrees(),
+                    prime_meridian: body.rotation.prime_meridian.to_degrees(),
+                    precession_period: body.rotation.precession_period,
+                    tidally_locked: body.rotation.tidally_locked,
+                    rotation_axis: None,
+                    shader: Some(body.shader),
+                    orbit: body.orbital_params.as_ref().map(OrbitDefinition::from_orbit),
+                    rings: body.rings.as_ref().map(|rings| RingsDefinition {
+                        inner_radius: rings.inner_radius,
+                        outer_radius: rings.outer_radius,
+                        tilt: rings.tilt.to_degrees(),
+                        tilt_axis: rings.tilt_axis.into(),
+                    }),
+                    physical: body.physical.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Construye los cuerpos, resolviendo los padres por nombre.
+    ///
+    /// Los padres pueden declararse en cualquier orden; los ciclos los
+    /// detecta [`SceneGraph`](crate::scene_graph::SceneGraph).
+    pub fn build(&self) -> Result<Vec<CelestialBody>, String> {
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        for (index, definition) in self.bodies.iter().enumerate() {
+            if indices.insert(definition.name.as_str(), index).is_some() {
+                return Err(format!("Cuerpo duplicado: '{}'", definition.name));
+            }
+        }
+
+        let mut bodies = Vec::with_capacity(self.bodies.len());
+        for definition in &self.bodies {
+            let name = definition.name.as_str();
+            if definition.radius <= 0.0 {
+                return Err(format!("'{}' debe tener un radio positivo", name));
+            }
+
+            let parent_index = match &definition.parent {
+                Some(parent) => Some(*indices.get(parent.as_str()).ok_or_else(|| {
+                    format!("El padre '{}' de '{}' no existe", parent, name)
+                })?),
+                None => None,
+            };
+
+            let orbital_params = match &definition.orbit {
+                Some(orbit) => Some(orbit.to_orbit().map_err(|e| format!("'{}': {}", name, e))?),
+                None => None,
+            };
+
+            let mut rotation = RotationModel {
+                period: definition.rotation_period,
+                obliquity: definition.obliquity.to_radians(),
+                axis_longitude: definition.axis_longitude.to_radians(),
+                prime_meridian: definition.prime_meridian.to_radians(),
+                tidally_locked: definition.tidally_locked,
+                precession_period: definition.precession_period,
+            };
+            if let Some(axis) = definition.rotation_axis {
+                let axis = Vec3::from(axis);
+                if axis.magnitude() <= f32::EPSILON {
+                    return Err(format!("'{}' tiene un eje de rotación nulo", name));
+                }
+                let axis = axis.normalize().cast::<f64>();
+                rotation.obliquity = axis.y.clamp(-1.0, 1.0).acos();
+                rotation.axis_longitude = (-axis.z).atan2(axis.x);
+            }
+            if rotation.tidally_locked && orbital_params.is_none() {
+                return Err(format!("'{}' no puede estar acoplado por marea sin órbita", name));
+            }
+
+            bodies.push(CelestialBody {
+                name: definition.name.clone(),
+                body_type: definition.body_type,
+                radius: definition.radius,
+                orbital_params,
+                rotation,
+                parent_index,
+                physical: definition.physical.clone(),
+                shader: definition
+                    .shader
+                    .unwrap_or_else(|| SurfaceShader::default_for(definition.body_type)),
+                rings: definition.rings.as_ref().map(|rings| Rings {
+                    inner_radius: rings.inner_radius,
+                    outer_radius: rings.outer_radius,
+                    tilt: rings.tilt.to_radians(),
+                    tilt_axis: Vec3::from(rings.tilt_axis),
+                }),
+            });
+        }
+
+        Ok(bodies)
+    }
+}
+
+impl OrbitDefinition {
+    fn from_orbit(orbit: &OrbitalParameters) -> Self {
+        let is_open = orbit.is_open();
+        Self {
+            semi_major_axis: if is_open { 0.0 } else { orbit.semi_major_axis },
+            eccentricity: orbit.eccentricity,
+            inclination: orbit.inclination.to_degrees(),
+            longitude_of_ascending_node: orbit.longitude_of_ascending_node.to_degrees(),
+            argument_of_periapsis: orbit.argument_of_periapsis.to_degrees(),
+            period: orbit.orbital_period,
+            mean_anomaly: orbit.initial_mean_anomaly.to_degrees(),
+            periapsis_distance: is_open.then(|| orbit.periapsis_distance()),
+            time_of_periapsis: is_open.then_some(orbit.time_of_periapsis),
+            gravitational_parameter: is_open.then_some(orbit.gravitational_parameter),
+            secular_rates: orbit.secular_rates.as_ref().map(|rates| SecularRatesDefinition {
+                semi_major_axis: rates.semi_major_axis,
+                eccentricity: rates.eccentricity,
+                inclination: rates.inclination.to_degrees(),
+                longitude_of_ascending_node: rates.longitude_of_ascending_node.to_degrees(),
+                argument_of_periapsis: rates.argument_of_periapsis.to_degrees(),
+            }),
+        }
+    }
+
+    fn to_orbit(&self) -> Result<OrbitalParameters, String> {
+        if self.eccentricity < 0.0 {
+            return Err(format!("excentricidad negativa: {}", self.eccentricity));
+        }
+
+        let is_open = self.eccentricity >= 1.0;
+        let semi_major_axis = if is_open {
+            let periapsis_distance = self.periapsis_distance.ok_or_else(|| {
+                "una órbita abierta (e ≥ 1) necesita periapsis_distance".to_string()
+            })?;
+            if periapsis_distance <= 0.0 {
+                return Err("la distancia del periapsis debe ser positiva".to_string());
+            }
+            if self.gravitational_parameter.is_none_or(|gm| gm <= 0.0) {
+                return Err("una órbita abierta necesita un gravitational_parameter positivo".to_string());
+            }
+            periapsis_distance
+        } else {
+            if self.semi_major_axis <= 0.0 {
+                return Err("el semieje mayor debe ser positivo".to_string());
+            }
+            if self.period == 0.0 {
+                return Err("el período orbital no puede ser cero".to_string());
+            }
+            self.semi_major_axis
+        };
+
+        Ok(OrbitalParameters {
+            semi_major_axis,
+            eccentricity: self.eccentricity,
+            inclination: self.inclination.to_radians(),
+            longitude_of_ascending_node: self.longitude_of_ascending_node.to_radians(),
+            argument_of_periapsis: self.argument_of_periapsis.to_radians(),
+            orbital_period: self.period,
+            initial_mean_anomaly: self.mean_anomaly.to_radians(),
+            gravitational_parameter: self.gravitational_parameter.unwrap_or(0.0),
+            time_of_periapsis: self.time_of_periapsis.unwrap_or(0.0),
+            secular_rates: self.secular_rates.as_ref().map(|rates| SecularRates {
+                semi_major_axis: rates.semi_major_axis,
+                eccentricity: rates.eccentricity,
+                inclination: rates.inclination.to_radians(),
+                longitude_of_ascending_node: rates.longitude_of_ascending_node.to_radians(),
+                argument_of_periapsis: rates.argument_of_periapsis.to_radians(),
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solar_system::SolarSystemBuilder;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance * expected.abs().max(1.0),
+            "{actual} != {expected}"
+        );
+    }
+
+    fn parse(text: &str) -> Scenario {
+        toml::from_str(text).expect("el TOML de prueba debe ser válido")
+    }
+
+    #[test]
+    fn default_system_survives_a_toml_round_trip() {
+        let original = SolarSystemBuilder::build_realistic();
+        let text = toml::to_string_pretty(&Scenario::from_bodies("Sistema Solar", &original, 500, 7))
+            .expect("el sistema por defecto debe serializarse");
+        let scenario: Scenario = toml::from_str(&text).expect("el TOML generado debe leerse");
+        assert_eq!(scenario.name, "Sistema Solar");
+        assert_eq!(scenario.asteroid_count, 500);
+        assert_eq!(scenario.seed, Some(7));
+
+        let rebuilt = scenario.build().expect("el sistema por defecto debe reconstruirse");
+        assert_eq!(rebuilt.len(), original.len());
+        for (before, after) in original.iter().zip(&rebuilt) {
+            assert_eq!(after.name, before.name);
+            assert_eq!(after.body_type, before.body_type);
+            assert_eq!(after.parent_index, before.parent_index, "{}", before.name);
+            assert_eq!(after.shader, before.shader, "{}", before.name);
+            assert_eq!(after.radius, before.radius);
+            assert_eq!(after.rings.is_some(), before.rings.is_some(), "{}", before.name);
+            assert_close(after.physical.gm, before.physical.gm, 1e-12);
+
+            let (before_orbit, after_orbit) = (before.orbital_params.as_ref(), after.orbital_params.as_ref());
+            assert_eq!(after_orbit.is_some(), before_orbit.is_some(), "{}", before.name);
+            for time in [0.0, 123.4, 3650.0] {
+                if let (Some(before_orbit), Some(after_orbit)) = (before_orbit, after_orbit) {
+                    let expected = before_orbit.get_position(time);
+                    let error = (after_orbit.get_position(time) - expected).magnitude();
+                    assert!(error <= 1e-9 * expected.magnitude().max(1.0), "{}: {error}", before.name);
+                }
+                let axis_error = (after.rotation.spin_axis(time, after_orbit)
+                    - before.rotation.spin_axis(time, before_orbit))
+                .magnitude();
+                assert!(axis_error < 1e-9, "{}: {axis_error}", before.name);
+                assert_close(
+                    after.rotation.spin_angle(time, after_orbit),
+                    before.rotation.spin_angle(time, before_orbit),
+                    1e-9,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parents_may_be_declared_after_their_children() {
+        let bodies = parse(
+            r#"
+            name = "Orden libre"
+
+            [[body]]
+            name = "Luna"
+            type = "moon"
+            radius = 5.0
+            parent = "Mundo"
+            orbit = { semi_major_axis = 60.0, period = 27.3 }
+
+            [[body]]
+            name = "Mundo"
+            type = "planet"
+            radius = 20.0
+            "#,
+        )
+        .build()
+        .expect("el orden de declaración no debe importar");
+        assert_eq!(bodies[0].parent_index, Some(1));
+        assert_eq!(bodies[1].parent_index, None);
+    }
+
+    #[test]
+    fn build_rejects_a_missing_parent() {
+        let result = parse(
+            r#"
+            name = "Huérfano"
+
+            [[body]]
+            name = "Luna"
+            type = "moon"
+            radius = 5.0
+            parent = "Nadie"
+            orbit = { semi_major_axis = 60.0, period = 27.3 }
+            "#,
+        )
+        .build();
+        assert_eq!(result.err().as_deref(), Some("El padre 'Nadie' de 'Luna' no existe"));
+    }
+
+    #[test]
+    fn build_rejects_a_duplicate_name() {
+        let result = parse(
+            r#"
+            name = "Gemelos"
+
+            [[body]]
+            name = "Mundo"
+            type = "planet"
+            radius = 20.0
+
+            [[body]]
+            name = "Mundo"
+            type = "planet"
+            radius = 10.0
+            "#,
+        )
+        .build();
+        assert_eq!(result.err().as_deref(), Some("Cuerpo duplicado: 'Mundo'"));
+    }
+
+    #[test]
+    fn open_orbit_needs_periapsis_distance() {
+        let result = parse(
+            r#"
+            name = "Visitante"
+
+            [[body]]
+            name = "Cometa"
+            type = "asteroid"
+            radius = 1.0
+
+            [body.orbit]
+            eccentricity = 1.2
+            gravitational_parameter = 2.0e5
+            "#,
+        )
+        .build();
+        assert_eq!(
+            result.err().as_deref(),
+            Some("'Cometa': una órbita abierta (e ≥ 1) necesita periapsis_distance")
+        );
+    }
+
+    #[test]
+    fn legacy_rotation_axis_becomes_obliquity() {
+        let bodies = parse(
+            r#"
+            name = "Eje antiguo"
+
+            [[body]]
+            name = "Mundo"
+            type = "planet"
+            radius = 20.0
+            rotation_period = 1.0
+            rotation_axis = [1.0, 1.0, 0.0]
+            "#,
+        )
+        .build()
+        .expect("el formato anterior debe seguir leyéndose");
+        assert_close(bodies[0].rotation.obliquity, 45f64.to_radians(), 1e-6);
+        assert_close(bodies[0].rotation.axis_longitude, 0.0, 1e-6);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::celestial_body::CelestialBody;
+use nalgebra_glm::DVec3;
+use std::collections::HashMap;
+
+/// Jerarquía padre/hijo de los cuerpos de la escena.
+///
+/// Valida las relaciones al construirse (padres existentes, sin ciclos, sin
+/// nombres repetidos) y calcula un orden topológico: cada cuerpo aparece
+/// después de su padre, sea cual sea su posición en la lista. Los
+/// identificadores son los índices en la lista de cuerpos.
+pub struct SceneGraph {
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    order: Vec<usize>,
+    names: HashMap<String, usize>,
+}
+
+impl SceneGraph {
+    pub fn new(bodies: &[CelestialBody]) -> Result<Self, String> {
+        let count = bodies.len();
+        let mut names = HashMap::with_capacity(count);
+        let mut children = vec![Vec::new(); count];
+        let parents: Vec<Option<usize>> = bodies.iter().map(|b| b.parent_index).collect();
+
+        for (id, body) in bodies.iter().enumerate() {
+            if names.insert(body.name.clone(), id).is_some() {
+                return Err(format!("Cuerpo duplicado: '{}'", body.name));
+            }
+
+            if let Some(parent) = body.parent_index {
+                if parent >= count {
+                    return Err(format!(
+                        "'{}' tiene un padre inexistente (índice {})",
+                        body.name, parent
+                    ));
+                }
+                if parent == id {
+                    return Err(format!("'{}' no puede ser su propio padre", body.name));
+                }
+                children[parent].push(id);
+            }
+        }
+
+        // Kahn: se parte de las raíces y se añade cada hijo tras su padre.
+        let mut order: Vec<usize> = (0..count).filter(|&id| parents[id].is_none()).collect();
+        let mut next = 0;
+        while next < order.len() {
+            let id = order[next];
+            order.extend_from_slice(&children[id]);
+            next += 1;
+        }
+
+        if order.len() < count {
+            let mut visited = vec![false; count];
+            for &id in &order {
+                visited[id] = true;
+            }
+            let start = (0..count).find(|&id| !visited[id]).unwrap_or(0);
+            return Err(format!(
+                "Ciclo en la jerarquía: {}",
+                describe_cycle(bodies, &parents, start)
+            ));
+        }
+
+        Ok(Self {
+            parents,
+            children,
+            order,
+            names,
+        })
+    }
+
+    /// Busca un cuerpo por nombre.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    pub fn parent(&self, id: usize) -> Option<usize> {
+        self.parents[id]
+    }
+
+    pub fn children(&self, id: usize) -> &[usize] {
+        &self.children[id]
+    }
+
+    /// Recorrido en profundidad `(id, nivel)`: cada cuerpo seguido de sus
+    /// satélites, para listados jerárquicos.
+    pub fn depth_first(&self) -> Vec<(usize, usize)> {
+        let mut result = Vec::with_capacity(self.parents.len());
+        let mut stack: Vec<(usize, usize)> = (0..self.parents.len())
+            .rev()
+            .filter(|&id| self.parents[id].is_none())
+            .map(|id| (id, 0))
+            .collect();
+        while let Some((id, depth)) = stack.pop() {
+            result.push((id, depth));
+            stack.extend(self.children(id).iter().rev().map(|&child| (child, depth + 1)));
+        }
+        result
+    }
+
+    /// Posiciones absolutas de todos los cuerpos en `time` según sus órbitas.
+    pub fn world_positions(&self, bodies: &[CelestialBody], time: f64) -> Vec<DVec3> {
+        let mut world = vec![DVec3::zeros(); bodies.len()];
+        for &id in &self.order {
+            let parent_pos = self.parents[id].map(|p| world[p]);
+            world[id] = bodies[id].get_world_position(time, parent_pos);
+        }
+        world
+    }
+
+    /// Convierte posiciones relativas al padre en absolutas.
+    pub fn compose(&self, local: &[DVec3]) -> Vec<DVec3> {
+        let mut world = vec![DVec3::zeros(); local.len()];
+        for &id in &self.order {
+            let parent_pos = self.parents[id].map(|p| world[p]).unwrap_or_else(DVec3::zeros);
+            world[id] = parent_pos + local[id];
+        }
+        world
+    }
+}
+
+/// Nombres del ciclo alcanzado subiendo por los padres desde `start`.
+fn describe_cycle(bodies: &[CelestialBody], parents: &[Option<usize>], start: usize) -> String {
+    let mut path = vec![start];
+    let mut current = start;
+    while let Some(parent) = parents[current] {
+        if let Some(position) = path.iter().position(|&id| id == parent) {
+            let mut names: Vec<&str> = path[position..]
+                .iter()
+                .map(|&id| bodies[id].name.as_str())
+                .collect();
+            names.push(&bodies[parent].name);
+            return names.join(" -> ");
+        }
+        path.push(parent);
+        current = parent;
+    }
+    bodies[start].name.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::celestial_body::{
+        CelestialType, OrbitalParameters, PhysicalProperties, RotationModel, SurfaceShader,
+    };
+
+    fn body(name: &str, parent_index: Option<usize>, distance: f64) -> CelestialBody {
+        let body_type = if parent_index.is_some() { CelestialType::Moon } else { CelestialType::Star };
+        CelestialBody {
+            name: name.to_string(),
+            body_type,
+            radius: 1.0,
+            orbital_params: parent_index.map(|_| OrbitalParameters::circular(distance, 10.0)),
+            rotation: RotationModel::default(),
+            parent_index,
+            physical: PhysicalProperties::default(),
+            shader: SurfaceShader::default_for(body_type),
+            rings: None,
+        }
+    }
+
+    #[test]
+    fn cycle_is_reported_by_name() {
+        let bodies = [
+            body("Sol", None, 0.0),
+            body("Suelta", Some(2), 5.0),
+            body("Alfa", Some(3), 10.0),
+            body("Beta", Some(2), 20.0),
+        ];
+        let error = SceneGraph::new(&bodies).err().expect("el ciclo debe rechazarse");
+        assert_eq!(error, "Ciclo en la jerarquía: Alfa -> Beta -> Alfa");
+    }
+
+    #[test]
+    fn child_declared_before_its_parent_is_placed_after_it() {
+        let bodies = [
+            body("Luna", Some(2), 10.0),
+            body("Sol", None, 0.0),
+            body("Planeta", Some(1), 100.0),
+        ];
+        let graph = SceneGraph::new(&bodies).expect("el orden de declaración no debe importar");
+        assert_eq!(graph.order, [1, 2, 0]);
+        assert_eq!(graph.children(1), [2]);
+        assert_eq!(graph.depth_first(), [(1, 0), (2, 1), (0, 2)]);
+
+        // La luna se compone sobre la posición ya calculada de su planeta.
+        let world = graph.world_positions(&bodies, 0.0);
+        assert_eq!(world[1], DVec3::zeros());
+        assert!((world[2].magnitude() - 100.0).abs() < 1e-9);
+        assert!(((world[0] - world[2]).magnitude() - 10.0).abs() < 1e-9);
+    }
+}
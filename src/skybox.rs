@@ -1,6 +1,7 @@
 use crate::framebuffer::{Framebuffer, Color};
 use nalgebra_glm::{Vec3, Mat4};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 pub struct Skybox {
     stars: Vec<Star>,
@@ -13,8 +14,8 @@ struct Star {
 }
 
 impl Skybox {
-    pub fn new(star_count: usize) -> Self {
-        let mut rng = rand::rng();
+    pub fn new(star_count: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
         let mut stars = Vec::with_capacity(star_count);
 
         for _ in 0..star_count {
@@ -89,7 +90,7 @@ impl Skybox {
                         let x = screen_x + dx as usize;
                         let y = screen_y + dy as usize;
                         if x < width as usize && y < height as usize {
-                            framebuffer.set_pixel(x, y, color, 0.999);
+                            framebuffer.set_pixel(x, y, color, 1.0);
                         }
                     }
                 }
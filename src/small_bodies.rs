@@ -0,0 +1,343 @@
+use crate::celestial_body::{CelestialBody, CelestialType, OrbitalParameters};
+use rand::Rng;
+use std::f64::consts::PI;
+
+/// Resonancias de movimiento medio con el planeta gigante que vacían el
+/// cinturón principal (huecos de Kirkwood), como `(p, q)`: el asteroide da
+/// `p` vueltas por cada `q` del gigante.
+const KIRKWOOD_RESONANCES: [(f64, f64); 5] = [(4.0, 1.0), (3.0, 1.0), (5.0, 2.0), (7.0, 3.0), (2.0, 1.0)];
+/// Semiancho de cada hueco, en fracciones del semieje mayor del gigante.
+const KIRKWOOD_HALF_WIDTH: f64 = 0.005;
+/// Bordes del cinturón principal respecto al gigante (2.1–3.3 UA con Júpiter).
+const MAIN_BELT_RANGE: (f64, f64) = (0.40, 0.64);
+/// Borde del cinturón de Kuiper clásico respecto al planeta más externo
+/// (42–48 UA con Neptuno).
+const CLASSICAL_KUIPER_RANGE: (f64, f64) = (1.4, 1.6);
+/// Fracción del cinturón de Kuiper atrapada en la resonancia 3:2 (plutinos).
+const PLUTINO_FRACTION: f64 = 0.25;
+/// Perihelios y semiejes del disco disperso respecto al planeta más externo.
+const SCATTERED_PERIAPSIS_RANGE: (f64, f64) = (1.0, 1.25);
+const SCATTERED_SEMI_MAJOR_AXIS_RANGE: (f64, f64) = (1.65, 5.0);
+/// Proporción de troyanos en L4 (el campo griego es más numeroso).
+const L4_FRACTION: f64 = 0.6;
+
+/// Familias de cuerpos menores que se generan alrededor del cuerpo central.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Population {
+    /// Cinturón principal por dentro del gigante, con los huecos de Kirkwood.
+    MainBelt,
+    /// Troyanos agrupados en L4 y L5 del gigante.
+    Trojan,
+    /// Cinturón de Kuiper: clásicos de baja excentricidad y plutinos.
+    Kuiper,
+    /// Disco disperso: órbitas excéntricas con el perihelio cerca del
+    /// planeta más externo.
+    ScatteredDisc,
+}
+
+impl Population {
+    pub const ALL: [Population; 4] =
+        [Population::MainBelt, Population::Trojan, Population::Kuiper, Population::ScatteredDisc];
+
+    /// Fracción del total de asteroides que corresponde a la familia.
+    fn share(self) -> f64 {
+        match self {
+            Population::MainBelt => 0.70,
+            Population::Trojan => 0.12,
+            Population::Kuiper => 0.13,
+            Population::ScatteredDisc => 0.05,
+        }
+    }
+
+    /// Reparte `count` cuerpos entre las familias; el redondeo se lo queda el
+    /// cinturón principal.
+    pub fn split(count: usize) -> [(Population, usize); 4] {
+        let mut counts = Self::ALL.map(|population| {
+            (population, (count as f64 * population.share()).floor() as usize)
+        });
+        let assigned: usize = counts.iter().map(|(_, n)| n).sum();
+        counts[0].1 += count - assigned;
+        counts
+    }
+}
+
+/// Generador de órbitas de cuerpos menores coherentes con la escena.
+///
+/// Las familias se sitúan respecto al planeta más masivo del nivel raíz (el
+/// gigante: Júpiter) y al más externo (Neptuno), así que un escenario propio
+/// obtiene sus propios huecos, troyanos y cinturón exterior. Los períodos
+/// salen de la tercera ley de Kepler con el GM que implica la órbita del
+/// gigante, de modo que las resonancias se mantienen exactas.
+pub struct SmallBodyPopulations {
+    central_gm: f64,
+    giant: OrbitalParameters,
+    outer: OrbitalParameters,
+}
+
+impl SmallBodyPopulations {
+    /// `None` si la escena no tiene planetas con órbita cerrada alrededor del
+    /// cuerpo central.
+    pub fn new(bodies: &[CelestialBody]) -> Option<Self> {
+        let planets: Vec<(&CelestialBody, &OrbitalParameters)> = bodies
+            .iter()
+            .filter(|body| body.parent_index.is_none() && body.body_type != CelestialType::Asteroid)
+            .filter_map(|body| {
+                let params = body.orbital_params.as_ref()?;
+                (!params.is_open() && params.orbital_period > 0.0).then_some((body, params))
+            })
+            .collect();
+
+        let (_, giant) = planets.iter().max_by(|a, b| a.0.physical.gm.total_cmp(&b.0.physical.gm))?;
+        let (_, outer) = planets
+            .iter()
+            .max_by(|a, b| a.1.semi_major_axis.total_cmp(&b.1.semi_major_axis))?;
+
+        // Elementos de J2000: los asteroides no llevan deriva secular.
+        let giant = giant.at_time(0.0);
+        Some(Self {
+            central_gm: giant.central_gm(),
+            giant,
+            outer: outer.at_time(0.0),
+        })
+    }
+
+    /// Órbita aleatoria de un miembro de `population`.
+    pub fn sample(&self, population: Population, rng: &mut impl Rng) -> OrbitalParameters {
+        match population {
+            Population::MainBelt => self.main_belt(rng),
+            Population::Trojan => self.trojan(rng),
+            Population::Kuiper => self.kuiper(rng),
+            Population::ScatteredDisc => self.scattered_disc(rng),
+        }
+    }
+
+    /// Semiejes uniformes entre los bordes del cinturón, descartando los que
+    /// caen en un hueco de Kirkwood (con bordes suaves).
+    fn main_belt(&self, rng: &mut impl Rng) -> OrbitalParameters {
+        let giant_axis = self.giant.semi_major_axis;
+        let semi_major_axis = loop {
+            let fraction = rng.random_range(MAIN_BELT_RANGE.0..MAIN_BELT_RANGE.1);
+            let depletion = KIRKWOOD_RESONANCES
+                .iter()
+                .map(|(p, q)| {
+                    let distance = (fraction - (q / p).powf(2.0 / 3.0)).abs() / KIRKWOOD_HALF_WIDTH;
+                    (1.0 - distance * distance).max(0.0)
+                })
+                .fold(0.0, f64::max);
+            if rng.random::<f64>() >= depletion {
+                break fraction * giant_axis;
+            }
+        };
+
+        let eccentricity = rayleigh(rng, 0.1).min(0.35);
+        let inclination = rayleigh(rng, 7f64.to_radians());
+        self.random_orbit(rng, semi_major_axis, eccentricity, inclination)
+    }
+
+    /// Coorbitales del gigante: mismo período y longitud media 60° por
+    /// delante (L4) o por detrás (L5), con la dispersión de la libración.
+    fn trojan(&self, rng: &mut impl Rng) -> OrbitalParameters {
+        let side = if rng.random::<f64>() < L4_FRACTION { 1.0 } else { -1.0 };
+        let libration = normal(rng, 12f64.to_radians());
+        let longitude = mean_longitude(&self.giant) + side * PI / 3.0 + libration;
+
+        let eccentricity = rayleigh(rng, 0.05).min(0.2);
+        let inclination = rayleigh(rng, 10f64.to_radians());
+        let mut orbit = self.random_orbit(rng, self.giant.semi_major_axis, eccentricity, inclination);
+        orbit.orbital_period = self.giant.orbital_period;
+        orbit.initial_mean_anomaly = (longitude - orbit.longitude_of_ascending_node
+            - orbit.argument_of_periapsis)
+            .rem_euclid(2.0 * PI);
+        orbit
+    }
+
+    /// Clásicos fríos y calientes entre 42 y 48 UA, y plutinos en la 3:2 con
+    /// el planeta exterior, con el perihelio lejos de él.
+    fn kuiper(&self, rng: &mut impl Rng) -> OrbitalParameters {
+        let outer_axis = self.outer.semi_major_axis;
+
+        if rng.random::<f64>() < PLUTINO_FRACTION {
+            let period = self.outer.orbital_period * 1.5;
+            let semi_major_axis = (self.central_gm * (period / (2.0 * PI)).powi(2)).cbrt();
+            let eccentricity = rng.random_range(0.1..0.3);
+            let inclination = rayleigh(rng, 10f64.to_radians());
+            let mut orbit = self.random_orbit(rng, semi_major_axis, eccentricity, inclination);
+
+            // Ángulo resonante 3λ - 2λ' - ϖ librando en torno a 180°: el
+            // planeta nunca está cerca cuando el plutino pasa por el perihelio.
+            let longitude = rng.random_range(0.0..2.0 * PI);
+            let resonant_angle = PI + normal(rng, 30f64.to_radians());
+            let perihelion = 3.0 * longitude - 2.0 * mean_longitude(&self.outer) - resonant_angle;
+            orbit.orbital_period = period;
+            orbit.argument_of_periapsis =
+                (perihelion - orbit.longitude_of_ascending_node).rem_euclid(2.0 * PI);
+            orbit.initial_mean_anomaly = (longitude - perihelion).rem_euclid(2.0 * PI);
+            return orbit;
+        }
+
+        let semi_major_axis =
+            rng.random_range(CLASSICAL_KUIPER_RANGE.0..CLASSICAL_KUIPER_RANGE.1) * outer_axis;
+        let eccentricity = rayleigh(rng, 0.05).min(0.2);
+        // La mitad de la población es "fría" (casi coplanar) y la otra "caliente".
+        let spread = if rng.random::<bool>() { 2.0 } else { 12.0 };
+        let inclination = rayleigh(rng, f64::to_radians(spread));
+        self.random_orbit(rng, semi_major_axis, eccentricity, inclination)
+    }
+
+    /// Perihelio cerca del planeta exterior y semieje log-uniforme hasta
+    /// cinco veces su distancia.
+    fn scattered_disc(&self, rng: &mut impl Rng) -> OrbitalParameters {
+        let outer_axis = self.outer.semi_major_axis;
+        let periapsis =
+            rng.random_range(SCATTERED_PERIAPSIS_RANGE.0..SCATTERED_PERIAPSIS_RANGE.1) * outer_axis;
+        let (low, high) = SCATTERED_SEMI_MAJOR_AXIS_RANGE;
+        let semi_major_axis = (rng.random_range(low.ln()..high.ln())).exp() * outer_axis;
+
+        let eccentricity = 1.0 - periapsis / semi_major_axis;
+        let inclination = rayleigh(rng, 15f64.to_radians());
+        self.random_orbit(rng, semi_major_axis, eccentricity, inclination)
+    }
+
+    /// Órbita con nodo, periapsis y anomalía uniformes y el período de Kepler.
+    fn random_orbit(
+        &self,
+        rng: &mut impl Rng,
+        semi_major_axis: f64,
+        eccentricity: f64,
+        inclination: f64,
+    ) -> OrbitalParameters {
+        OrbitalParameters {
+            semi_major_axis,
+            eccentricity,
+            inclination,
+            longitude_of_ascending_node: rng.random_range(0.0..2.0 * PI),
+            argument_of_periapsis: rng.random_range(0.0..2.0 * PI),
+            orbital_period: 2.0 * PI * (semi_major_axis.powi(3) / self.central_gm).sqrt(),
+            initial_mean_anomaly: rng.random_range(0.0..2.0 * PI),
+            gravitational_parameter: 0.0,
+            time_of_periapsis: 0.0,
+            secular_rates: None,
+        }
+    }
+}
+
+/// Longitud media en J2000: `λ = Ω + ω + M`.
+fn mean_longitude(orbit: &OrbitalParameters) -> f64 {
+    orbit.longitude_of_ascending_node + orbit.argument_of_periapsis + orbit.initial_mean_anomaly
+}
+
+/// Muestra de Rayleigh (distribución típica de excentricidades e
+/// inclinaciones de una población dinámicamente relajada).
+fn rayleigh(rng: &mut impl Rng, sigma: f64) -> f64 {
+    sigma * (-2.0 * (1.0 - rng.random::<f64>()).ln()).sqrt()
+}
+
+/// Muestra normal centrada en 0 (Box-Muller).
+fn normal(rng: &mut impl Rng, sigma: f64) -> f64 {
+    let radius = (-2.0 * (1.0 - rng.random::<f64>()).ln()).sqrt();
+    sigma * radius * (2.0 * PI * rng.random::<f64>()).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::celestial_body::{PhysicalProperties, RotationModel, SurfaceShader};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn body(name: &str, orbital_params: Option<OrbitalParameters>, gm: f64) -> CelestialBody {
+        let body_type = if orbital_params.is_some() { CelestialType::Planet } else { CelestialType::Star };
+        CelestialBody {
+            name: name.to_string(),
+            body_type,
+            radius: 1.0,
+            orbital_params,
+            rotation: RotationModel::default(),
+            parent_index: None,
+            physical: PhysicalProperties { gm, ..Default::default() },
+            shader: SurfaceShader::default_for(body_type),
+            rings: None,
+        }
+    }
+
+    /// Sol, un gigante a 5.2 UA y un planeta exterior a 30 UA (1 UA = 100).
+    fn populations() -> SmallBodyPopulations {
+        let mu = 0.0296;
+        let orbit = |radius: f64| {
+            let mut params = OrbitalParameters::circular(radius, 2.0 * PI * (radius.powi(3) / mu).sqrt());
+            params.initial_mean_anomaly = 1.0;
+            params
+        };
+        let bodies = [
+            body("Sol", None, 1.3e11),
+            body("Gigante", Some(orbit(520.0)), 1.3e8),
+            body("Exterior", Some(orbit(3000.0)), 6.8e6),
+        ];
+        SmallBodyPopulations::new(&bodies).expect("la escena tiene planetas")
+    }
+
+    #[test]
+    fn split_assigns_every_body() {
+        for count in [0, 1, 7, 999, 10_000] {
+            let total: usize = Population::split(count).iter().map(|(_, n)| n).sum();
+            assert_eq!(total, count);
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_identical_orbits() {
+        let populations = populations();
+        let sample_all = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..200)
+                .map(|i| {
+                    let orbit = populations.sample(Population::ALL[i % 4], &mut rng);
+                    [
+                        orbit.semi_major_axis,
+                        orbit.eccentricity,
+                        orbit.inclination,
+                        orbit.longitude_of_ascending_node,
+                        orbit.argument_of_periapsis,
+                        orbit.orbital_period,
+                        orbit.initial_mean_anomaly,
+                    ]
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(sample_all(42), sample_all(42));
+        assert_ne!(sample_all(42), sample_all(43));
+    }
+
+    #[test]
+    fn main_belt_avoids_kirkwood_gaps() {
+        let populations = populations();
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples = 20_000;
+        let fractions: Vec<f64> = (0..samples)
+            .map(|_| populations.sample(Population::MainBelt, &mut rng).semi_major_axis / 520.0)
+            .collect();
+
+        // En el centro de cada hueco quedan muchos menos que con un reparto uniforme.
+        let band = KIRKWOOD_HALF_WIDTH / 2.0;
+        let uniform = samples as f64 * 2.0 * band / (MAIN_BELT_RANGE.1 - MAIN_BELT_RANGE.0);
+        for (p, q) in KIRKWOOD_RESONANCES {
+            let center = (q / p).powf(2.0 / 3.0);
+            if center - band < MAIN_BELT_RANGE.0 || center + band > MAIN_BELT_RANGE.1 {
+                continue;
+            }
+            let inside = fractions.iter().filter(|f| (*f - center).abs() < band).count();
+            assert!((inside as f64) < 0.5 * uniform, "hueco {p}:{q} con {inside} asteroides");
+        }
+    }
+
+    #[test]
+    fn trojans_share_the_giant_period() {
+        let populations = populations();
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..100 {
+            let orbit = populations.sample(Population::Trojan, &mut rng);
+            assert_eq!(orbit.orbital_period, populations.giant.orbital_period);
+        }
+    }
+}
@@ -1,7 +1,259 @@
+use crate::asteroid_belt::AsteroidBelt;
 use crate::celestial_body::*;
+use crate::framebuffer::Color;
+use crate::small_bodies::{Population, SmallBodyPopulations};
 use nalgebra_glm::Vec3;
-use std::f32::consts::PI;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Unidades de simulación por unidad astronómica (la Tierra orbita a 7480).
+pub const AU: f64 = 7480.0;
+
+/// Elementos keplerianos medios de JPL ("Approximate Positions of the
+/// Planets", tabla 1, válida 1800–2050) respecto a la eclíptica y equinoccio
+/// J2000.
+///
+/// Por planeta: `[a (UA), e, I (°), L (°), ϖ (°), Ω (°)]` en J2000 y sus tasas
+/// por siglo juliano. "Tierra" es el baricentro Tierra-Luna.
+const JPL_ELEMENTS: [(&str, [f64; 6], [f64; 6]); 8] = [
+    (
+        "Mercurio",
+        [0.38709927, 0.20563593, 7.00497902, 252.25032350, 77.45779628, 48.33076593],
+        [0.00000037, 0.00001906, -0.00594749, 149472.67411175, 0.16047689, -0.12534081],
+    ),
+    (
+        "Venus",
+        [0.72333566, 0.00677672, 3.39467605, 181.97909950, 131.60246718, 76.67984255],
+        [0.00000390, -0.00004107, -0.00078890, 58517.81538729, 0.00268329, -0.27769418],
+    ),
+    (
+        "Tierra",
+        [1.00000261, 0.01671123, -0.00001531, 100.46457166, 102.93768193, 0.0],
+        [0.00000562, -0.00004392, -0.01294668, 35999.37244981, 0.32327364, 0.0],
+    ),
+    (
+        "Marte",
+        [1.52371034, 0.09339410, 1.84969142, -4.55343205, -23.94362959, 49.55953891],
+        [0.00001847, 0.00007882, -0.00813131, 19140.30268499, 0.44441088, -0.29257343],
+    ),
+    (
+        "Júpiter",
+        [5.20288700, 0.04838624, 1.30439695, 34.39644051, 14.72847983, 100.47390909],
+        [-0.00011607, -0.00013253, -0.00183714, 3034.74612775, 0.21252668, 0.20469106],
+    ),
+    (
+        "Saturno",
+        [9.53667594, 0.05386179, 2.48599187, 49.95424423, 92.59887831, 113.66242448],
+        [-0.00125060, -0.00050991, 0.00193609, 1222.49362201, -0.41897216, -0.28867794],
+    ),
+    (
+        "Urano",
+        [19.18916464, 0.04725744, 0.77263783, 313.23810451, 170.95427630, 74.01692503],
+        [-0.00196176, -0.00004397, -0.00242939, 428.48202785, 0.40805281, 0.04240589],
+    ),
+    (
+        "Neptuno",
+        [30.06992276, 0.00859048, 1.77004347, -55.12002969, 44.96476227, 131.78422574],
+        [0.00026291, 0.00005105, 0.00035372, 218.45945325, -0.32241464, -0.00508664],
+    ),
+];
+
+/// Órbita de un planeta a partir de la tabla de JPL.
+fn jpl_orbit(name: &str) -> OrbitalParameters {
+    let (_, elements, rates) = JPL_ELEMENTS
+        .iter()
+        .find(|(planet, _, _)| *planet == name)
+        .unwrap_or_else(|| panic!("sin elementos JPL para {}", name));
+
+    OrbitalParameters::from_mean_elements(*elements, *rates, AU)
+}
+
+/// Datos físicos reales por cuerpo: GM (km³/s²), densidad media (g/cm³),
+/// radio ecuatorial y polar (km), albedo geométrico, temperatura media (K),
+/// atmósfera y descripción.
+const PHYSICAL_DATA: [(&str, [f64; 6], &str, &str); 19] = [
+    (
+        "Sol",
+        [1.32712440018e11, 1.408, 695700.0, 695700.0, 0.0, 5772.0],
+        "H 73%, He 25% (fotosfera)",
+        "Estrella G2V con el 99.86% de la masa del sistema solar.",
+    ),
+    (
+        "Mercurio",
+        [22031.78, 5.427, 2440.5, 2438.3, 0.142, 440.0],
+        "Exosfera tenue (O, Na, H, He)",
+        "El planeta más cercano al Sol, con enormes contrastes térmicos.",
+    ),
+    (
+        "Venus",
+        [324858.59, 5.243, 6051.8, 6051.8, 0.689, 737.0],
+        "CO2 96.5%, N2 3.5%",
+        "Efecto invernadero desbocado: la superficie más caliente del sistema.",
+    ),
+    (
+        "Tierra",
+        [398600.44, 5.514, 6378.137, 6356.752, 0.434, 288.0],
+        "N2 78%, O2 21%, Ar 0.9%",
+        "Único mundo conocido con agua líquida en superficie y vida.",
+    ),
+    (
+        "Luna",
+        [4902.80, 3.344, 1738.1, 1736.0, 0.12, 250.0],
+        "",
+        "Rota de forma síncrona y estabiliza la inclinación del eje terrestre.",
+    ),
+    (
+        "Marte",
+        [42828.37, 3.934, 3396.2, 3376.2, 0.170, 210.0],
+        "CO2 95%, N2 2.8%, Ar 2%",
+        "Óxido de hierro en superficie y el volcán más alto del sistema.",
+    ),
+    (
+        "Fobos",
+        [7.087e-4, 1.876, 13.0, 9.1, 0.071, 233.0],
+        "",
+        "Luna irregular que cae lentamente hacia Marte.",
+    ),
+    (
+        "Deimos",
+        [9.62e-5, 1.471, 7.8, 5.1, 0.068, 233.0],
+        "",
+        "La menor de las lunas marcianas, quizá un asteroide capturado.",
+    ),
+    (
+        "Júpiter",
+        [126686534.0, 1.326, 71492.0, 66854.0, 0.538, 165.0],
+        "H2 90%, He 10%",
+        "El mayor planeta; la Gran Mancha Roja es una tormenta de siglos.",
+    ),
+    (
+        "Ío",
+        [5959.9, 3.528, 1829.4, 1815.7, 0.63, 110.0],
+        "SO2 (tenue)",
+        "El cuerpo con mayor actividad volcánica del sistema solar.",
+    ),
+    (
+        "Europa",
+        [3202.7, 3.013, 1560.8, 1560.8, 0.67, 102.0],
+        "O2 (tenue)",
+        "Corteza de hielo sobre un probable océano de agua salada.",
+    ),
+    (
+        "Ganimedes",
+        [9887.8, 1.942, 2634.1, 2634.1, 0.43, 110.0],
+        "O2 (tenue)",
+        "La mayor luna del sistema solar, con campo magnético propio.",
+    ),
+    (
+        "Calisto",
+        [7179.3, 1.834, 2410.3, 2410.3, 0.22, 134.0],
+        "CO2 (tenue)",
+        "Superficie antigua, saturada de cráteres.",
+    ),
+    (
+        "Saturno",
+        [37931187.0, 0.687, 60268.0, 54364.0, 0.499, 134.0],
+        "H2 96%, He 3%",
+        "Menos denso que el agua; sus anillos son de hielo y roca.",
+    ),
+    (
+        "Titán",
+        [8978.1, 1.882, 2574.7, 2574.7, 0.22, 94.0],
+        "N2 95%, CH4 5%",
+        "Única luna con atmósfera densa y lagos de metano líquido.",
+    ),
+    (
+        "Rea",
+        [153.94, 1.236, 763.8, 763.8, 0.949, 76.0],
+        "O2, CO2 (exosfera)",
+        "Segunda luna de Saturno en tamaño, de hielo casi puro.",
+    ),
+    (
+        "Encélado",
+        [7.211, 1.609, 256.6, 248.3, 1.375, 75.0],
+        "H2O (géiseres)",
+        "Sus géiseres de agua en el polo sur alimentan el anillo E.",
+    ),
+    (
+        "Urano",
+        [5793939.0, 1.27, 25559.0, 24973.0, 0.488, 76.0],
+        "H2 83%, He 15%, CH4 2%",
+        "Gira tumbado: su eje está inclinado 98 grados.",
+    ),
+    (
+        "Neptuno",
+        [6836529.0, 1.638, 24764.0, 24341.0, 0.442, 72.0],
+        "H2 80%, He 19%, CH4 1.5%",
+        "Tiene los vientos más rápidos del sistema solar.",
+    ),
+];
+
+/// Rotación por cuerpo: `[período sidéreo (días), oblicuidad (°), longitud
+/// del eje (°), período de precesión (días)]` y si está acoplado por marea.
+///
+/// Los períodos negativos son giros retrógrados. Oblicuidades respecto al
+/// plano orbital; la longitud del eje es la eclíptica del polo norte según
+/// la IAU (irrelevante para las lunas acopladas, casi sin inclinación).
+const ROTATION_DATA: [(&str, [f64; 4], bool); 19] = [
+    ("Sol", [25.0, 7.25, 345.8, 0.0], false),
+    ("Mercurio", [58.6, 0.03, 318.2, 0.0], false),
+    ("Venus", [-243.0, 2.64, 30.2, 0.0], false),
+    // Precesión de los equinoccios: 25 772 años, retrógrada.
+    ("Tierra", [1.0, 23.44, 90.0, -9.413e6], false),
+    ("Luna", [27.3, 6.68, 0.0, 0.0], true),
+    ("Marte", [1.03, 25.19, 352.9, 0.0], false),
+    ("Fobos", [0.32, 0.0, 0.0, 0.0], true),
+    ("Deimos", [1.26, 0.0, 0.0, 0.0], true),
+    ("Júpiter", [0.4, 3.13, 247.8, 0.0], false),
+    ("Ío", [1.77, 0.0, 0.0, 0.0], true),
+    ("Europa", [3.55, 0.0, 0.0, 0.0], true),
+    ("Ganimedes", [7.15, 0.0, 0.0, 0.0], true),
+    ("Calisto", [16.69, 0.0, 0.0, 0.0], true),
+    ("Saturno", [0.45, 26.73, 79.5, 0.0], false),
+    ("Titán", [15.95, 0.0, 0.0, 0.0], true),
+    ("Rea", [4.52, 0.0, 0.0, 0.0], true),
+    ("Encélado", [1.37, 0.0, 0.0, 0.0], true),
+    ("Urano", [-0.72, 82.23, 257.6, 0.0], false),
+    ("Neptuno", [0.67, 28.32, 319.2, 0.0], false),
+];
+
+/// Modelo de rotación de un cuerpo a partir de la tabla.
+fn rotation(name: &str) -> RotationModel {
+    let (_, values, tidally_locked) = ROTATION_DATA
+        .iter()
+        .find(|(body, ..)| *body == name)
+        .unwrap_or_else(|| panic!("sin datos de rotación para {}", name));
+    let [period, obliquity, axis_longitude, precession_period] = *values;
+
+    RotationModel {
+        period,
+        obliquity: obliquity.to_radians(),
+        axis_longitude: axis_longitude.to_radians(),
+        prime_meridian: 0.0,
+        tidally_locked: *tidally_locked,
+        precession_period,
+    }
+}
+
+/// Propiedades físicas de un cuerpo a partir de la tabla.
+fn physical(name: &str) -> PhysicalProperties {
+    let (_, values, atmosphere, description) = PHYSICAL_DATA
+        .iter()
+        .find(|(body, ..)| *body == name)
+        .unwrap_or_else(|| panic!("sin datos físicos para {}", name));
+    let [gm, mean_density, equatorial_radius, polar_radius, albedo, surface_temperature] = *values;
+
+    PhysicalProperties {
+        gm,
+        mean_density,
+        equatorial_radius,
+        polar_radius,
+        albedo,
+        surface_temperature,
+        atmosphere: atmosphere.to_string(),
+        description: description.to_string(),
+    }
+}
 
 pub struct SolarSystemBuilder;
 
@@ -14,63 +266,47 @@ impl SolarSystemBuilder {
                 body_type: CelestialType::Star,
                 radius: 350.0,
                 orbital_params: None,
-                rotation_period: 25.0,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Sol"),
                 parent_index: None,
+                physical: physical("Sol"),
+                shader: SurfaceShader::Sun,
+                rings: None,
             },
             // MERCURIO (índice 1)
             CelestialBody {
                 name: "Mercurio".to_string(),
                 body_type: CelestialType::Planet,
                 radius: 12.0,
-                orbital_params: Some(OrbitalParameters {
-                    semi_major_axis: 2895.0,
-                    eccentricity: 0.206,
-                    inclination: 7.0_f32.to_radians(),
-                    longitude_of_ascending_node: 0.0,
-                    argument_of_periapsis: 0.0,
-                    orbital_period: 88.0,
-                    initial_mean_anomaly: 0.0,
-                }),
-                rotation_period: 58.6,
-                rotation_axis: Vec3::y(),
+                orbital_params: Some(jpl_orbit("Mercurio")),
+                rotation: rotation("Mercurio"),
                 parent_index: None,
+                physical: physical("Mercurio"),
+                shader: SurfaceShader::Mercury,
+                rings: None,
             },
             // VENUS (índice 2)
             CelestialBody {
                 name: "Venus".to_string(),
                 body_type: CelestialType::Planet,
                 radius: 30.0,
-                orbital_params: Some(OrbitalParameters {
-                    semi_major_axis: 5410.0,
-                    eccentricity: 0.007,
-                    inclination: 3.4_f32.to_radians(),
-                    longitude_of_ascending_node: 0.0,
-                    argument_of_periapsis: 0.0,
-                    orbital_period: 224.7,
-                    initial_mean_anomaly: PI / 4.0,
-                }),
-                rotation_period: -243.0,
-                rotation_axis: Vec3::y(),
+                orbital_params: Some(jpl_orbit("Venus")),
+                rotation: rotation("Venus"),
                 parent_index: None,
+                physical: physical("Venus"),
+                shader: SurfaceShader::Venus,
+                rings: None,
             },
             // TIERRA (índice 3)
             CelestialBody {
                 name: "Tierra".to_string(),
                 body_type: CelestialType::Planet,
                 radius: 32.0,
-                orbital_params: Some(OrbitalParameters {
-                    semi_major_axis: 7480.0,
-                    eccentricity: 0.017,
-                    inclination: 0.0,
-                    longitude_of_ascending_node: 0.0,
-                    argument_of_periapsis: 0.0,
-                    orbital_period: 365.25,
-                    initial_mean_anomaly: PI / 2.0,
-                }),
-                rotation_period: 1.0,
-                rotation_axis: Vec3::new(0.0, 1.0, 0.01).normalize(),
+                orbital_params: Some(jpl_orbit("Tierra")),
+                rotation: rotation("Tierra"),
                 parent_index: None,
+                physical: physical("Tierra"),
+                shader: SurfaceShader::Earth,
+                rings: None,
             },
             // LUNA (índice 4)
             CelestialBody {
@@ -78,27 +314,23 @@ impl SolarSystemBuilder {
                 body_type: CelestialType::Moon,
                 radius: 8.7,
                 orbital_params: Some(OrbitalParameters::circular(192.0, 27.3)),
-                rotation_period: 27.3,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Luna"),
                 parent_index: Some(3),
+                physical: physical("Luna"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
             // MARTE (índice 5)
             CelestialBody {
                 name: "Marte".to_string(),
                 body_type: CelestialType::Planet,
                 radius: 17.0,
-                orbital_params: Some(OrbitalParameters {
-                    semi_major_axis: 11395.0,
-                    eccentricity: 0.093,
-                    inclination: 1.85_f32.to_radians(),
-                    longitude_of_ascending_node: 0.0,
-                    argument_of_periapsis: 0.0,
-                    orbital_period: 687.0,
-                    initial_mean_anomaly: PI,
-                }),
-                rotation_period: 1.03,
-                rotation_axis: Vec3::y(),
+                orbital_params: Some(jpl_orbit("Marte")),
+                rotation: rotation("Marte"),
                 parent_index: None,
+                physical: physical("Marte"),
+                shader: SurfaceShader::Mars,
+                rings: None,
             },
             // FOBOS (índice 6)
             CelestialBody {
@@ -106,9 +338,11 @@ impl SolarSystemBuilder {
                 body_type: CelestialType::Moon,
                 radius: 3.5,
                 orbital_params: Some(OrbitalParameters::circular(47.0, 0.32)),
-                rotation_period: 0.32,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Fobos"),
                 parent_index: Some(5),
+                physical: physical("Fobos"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
             // DEIMOS (índice 7)
             CelestialBody {
@@ -116,9 +350,11 @@ impl SolarSystemBuilder {
                 body_type: CelestialType::Moon,
                 radius: 2.5,
                 orbital_params: Some(OrbitalParameters::circular(117.5, 1.26)),
-                rotation_period: 1.26,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Deimos"),
                 parent_index: Some(5),
+                physical: physical("Deimos"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
         ];
 
@@ -128,18 +364,12 @@ impl SolarSystemBuilder {
             name: "Júpiter".to_string(),
             body_type: CelestialType::Planet,
             radius: 350.0,
-            orbital_params: Some(OrbitalParameters {
-                semi_major_axis: 38925.0,
-                eccentricity: 0.048,
-                inclination: 1.3_f32.to_radians(),
-                longitude_of_ascending_node: 0.0,
-                argument_of_periapsis: 0.0,
-                orbital_period: 4332.6,
-                initial_mean_anomaly: PI * 1.5,
-            }),
-            rotation_period: 0.4,
-            rotation_axis: Vec3::y(),
+            orbital_params: Some(jpl_orbit("Júpiter")),
+            rotation: rotation("Júpiter"),
             parent_index: None,
+            physical: physical("Júpiter"),
+            shader: SurfaceShader::Jupiter,
+            rings: None,
         });
 
         // Lunas galileanas
@@ -149,36 +379,44 @@ impl SolarSystemBuilder {
                 body_type: CelestialType::Moon,
                 radius: 9.1,
                 orbital_params: Some(OrbitalParameters::circular(1055.0, 1.77)),
-                rotation_period: 1.77,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Ío"),
                 parent_index: Some(jupiter_idx),
+                physical: physical("Ío"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
             CelestialBody {
                 name: "Europa".to_string(),
                 body_type: CelestialType::Moon,
                 radius: 7.8,
                 orbital_params: Some(OrbitalParameters::circular(1681.0, 3.55)),
-                rotation_period: 3.55,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Europa"),
                 parent_index: Some(jupiter_idx),
+                physical: physical("Europa"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
             CelestialBody {
                 name: "Ganimedes".to_string(),
                 body_type: CelestialType::Moon,
                 radius: 13.1,
                 orbital_params: Some(OrbitalParameters::circular(2679.0, 7.15)),
-                rotation_period: 7.15,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Ganimedes"),
                 parent_index: Some(jupiter_idx),
+                physical: physical("Ganimedes"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
             CelestialBody {
                 name: "Calisto".to_string(),
                 body_type: CelestialType::Moon,
                 radius: 12.0,
                 orbital_params: Some(OrbitalParameters::circular(4712.0, 16.69)),
-                rotation_period: 16.69,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Calisto"),
                 parent_index: Some(jupiter_idx),
+                physical: physical("Calisto"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
         ]);
 
@@ -188,18 +426,17 @@ impl SolarSystemBuilder {
             name: "Saturno".to_string(),
             body_type: CelestialType::Planet,
             radius: 300.0,
-            orbital_params: Some(OrbitalParameters {
-                semi_major_axis: 71675.0,
-                eccentricity: 0.054,
-                inclination: 2.49_f32.to_radians(),
-                longitude_of_ascending_node: 0.0,
-                argument_of_periapsis: 0.0,
-                orbital_period: 10759.0,
-                initial_mean_anomaly: 0.0,
-            }),
-            rotation_period: 0.45,
-            rotation_axis: Vec3::new(0.0, 1.0, 0.1).normalize(),
+            orbital_params: Some(jpl_orbit("Saturno")),
+            rotation: rotation("Saturno"),
             parent_index: None,
+            physical: physical("Saturno"),
+            shader: SurfaceShader::Saturn,
+            rings: Some(Rings {
+                inner_radius: 1.3,
+                outer_radius: 2.0,
+                tilt: 0.0,
+                tilt_axis: Vec3::x(),
+            }),
         });
 
         bodies.extend(vec![
@@ -208,27 +445,33 @@ impl SolarSystemBuilder {
                 body_type: CelestialType::Moon,
                 radius: 12.9,
                 orbital_params: Some(OrbitalParameters::circular(3059.0, 15.95)),
-                rotation_period: 15.95,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Titán"),
                 parent_index: Some(saturn_idx),
+                physical: physical("Titán"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
             CelestialBody {
                 name: "Rea".to_string(),
                 body_type: CelestialType::Moon,
                 radius: 3.8,
                 orbital_params: Some(OrbitalParameters::circular(1318.0, 4.52)),
-                rotation_period: 4.52,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Rea"),
                 parent_index: Some(saturn_idx),
+                physical: physical("Rea"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
             CelestialBody {
                 name: "Encélado".to_string(),
                 body_type: CelestialType::Moon,
                 radius: 1.3,
                 orbital_params: Some(OrbitalParameters::circular(596.0, 1.37)),
-                rotation_period: 1.37,
-                rotation_axis: Vec3::y(),
+                rotation: rotation("Encélado"),
                 parent_index: Some(saturn_idx),
+                physical: physical("Encélado"),
+                shader: SurfaceShader::Moon,
+                rings: None,
             },
         ]);
 
@@ -237,18 +480,12 @@ impl SolarSystemBuilder {
             name: "Urano".to_string(),
             body_type: CelestialType::Planet,
             radius: 127.0,
-            orbital_params: Some(OrbitalParameters {
-                semi_major_axis: 143625.0,
-                eccentricity: 0.047,
-                inclination: 0.77_f32.to_radians(),
-                longitude_of_ascending_node: 0.0,
-                argument_of_periapsis: 0.0,
-                orbital_period: 30688.5,
-                initial_mean_anomaly: PI / 3.0,
-            }),
-            rotation_period: -0.72,
-            rotation_axis: Vec3::new(0.98, 0.0, 0.17).normalize(),
+            orbital_params: Some(jpl_orbit("Urano")),
+            rotation: rotation("Urano"),
             parent_index: None,
+            physical: physical("Urano"),
+            shader: SurfaceShader::Uranus,
+            rings: None,
         });
 
         // NEPTUNO
@@ -256,62 +493,59 @@ impl SolarSystemBuilder {
             name: "Neptuno".to_string(),
             body_type: CelestialType::Planet,
             radius: 123.0,
-            orbital_params: Some(OrbitalParameters {
-                semi_major_axis: 224755.0,
-                eccentricity: 0.009,
-                inclination: 1.77_f32.to_radians(),
-                longitude_of_ascending_node: 0.0,
-                argument_of_periapsis: 0.0,
-                orbital_period: 60182.0,
-                initial_mean_anomaly: PI / 6.0,
-            }),
-            rotation_period: 0.67,
-            rotation_axis: Vec3::y(),
+            orbital_params: Some(jpl_orbit("Neptuno")),
+            rotation: rotation("Neptuno"),
             parent_index: None,
+            physical: physical("Neptuno"),
+            shader: SurfaceShader::Neptune,
+            rings: None,
         });
 
-        // CINTURÓN DE ASTEROIDES
-        bodies.extend(Self::create_asteroid_belt(100));
-
         bodies
     }
 
-    fn create_asteroid_belt(count: usize) -> Vec<CelestialBody> {
-        let mut rng = rand::rng();
-        let mut asteroids = Vec::new();
-
-        for i in 0..count {
-            let radius = rng.random_range(0.5..2.5);
-            let semi_major_axis = rng.random_range(16000.0..25000.0);
-            let eccentricity = rng.random_range(0.0..0.3);
-            let inclination = rng.random_range(-15.0..15.0_f32).to_radians();
-            let initial_anomaly = rng.random_range(0.0..2.0 * PI);
-            let period = rng.random_range(1000.0..2500.0);
+    /// Genera los cuerpos menores como un lote de instancias.
+    ///
+    /// Los asteroides no forman parte de la lista de `CelestialBody`: se
+    /// renderizan por instancias con [`AsteroidBelt`]. Las órbitas salen de
+    /// [`SmallBodyPopulations`] a partir de los planetas de `bodies`, y la
+    /// misma `seed` produce siempre el mismo cinturón.
+    pub fn create_asteroid_belt(bodies: &[CelestialBody], count: usize, seed: u64) -> AsteroidBelt {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut belt = AsteroidBelt::new();
+        let Some(populations) = SmallBodyPopulations::new(bodies) else {
+            return belt;
+        };
 
-            asteroids.push(CelestialBody {
-                name: format!("Asteroide-{}", i + 1),
-                body_type: CelestialType::Asteroid,
-                radius,
-                orbital_params: Some(OrbitalParameters {
-                    semi_major_axis,
-                    eccentricity,
-                    inclination,
-                    longitude_of_ascending_node: rng.random_range(0.0..2.0 * PI),
-                    argument_of_periapsis: rng.random_range(0.0..2.0 * PI),
-                    orbital_period: period,
-                    initial_mean_anomaly: initial_anomaly,
-                }),
-                rotation_period: rng.random_range(0.1..5.0),
-                rotation_axis: Vec3::new(
+        for (population, population_count) in Population::split(count) {
+            for _ in 0..population_count {
+                let orbit = populations.sample(population, &mut rng);
+                let rotation_axis = Vec3::new(
                     rng.random_range(-1.0..1.0),
                     rng.random_range(-1.0..1.0),
                     rng.random_range(-1.0..1.0),
                 )
-                .normalize(),
-                parent_index: None,
-            });
+                .normalize();
+
+                // Tono usado por el nivel de puntos: gris-marrón en el cinturón,
+                // rojizo oscuro en los troyanos y hielos rojizos más allá.
+                let (radius, tint) = match population {
+                    Population::MainBelt => (rng.random_range(0.5..2.5), (1.0, 0.92, 0.85)),
+                    Population::Trojan => (rng.random_range(0.5..2.5), (1.0, 0.8, 0.7)),
+                    Population::Kuiper => (rng.random_range(1.0..4.0), (1.0, 0.88, 0.8)),
+                    Population::ScatteredDisc => (rng.random_range(1.0..4.0), (0.9, 0.92, 1.0)),
+                };
+                let shade = rng.random_range(70..130) as f32;
+                let albedo = Color::new(
+                    (shade * tint.0) as u8,
+                    (shade * tint.1) as u8,
+                    (shade * tint.2) as u8,
+                );
+
+                belt.push(radius, &orbit, rng.random_range(0.1..5.0), rotation_axis, albedo);
+            }
         }
 
-        asteroids
+        belt
     }
 }
\ No newline at end of file
@@ -1,10 +1,11 @@
-use nalgebra_glm::Vec3;
+use nalgebra_glm::DVec3;
+use crate::camera::camera_relative;
 use crate::framebuffer::{Framebuffer, Color};
 use crate::renderer::Renderer;
 use nalgebra_glm::Mat4;
 
 pub struct ShipTrail {
-    positions: Vec<Vec3>,
+    positions: Vec<DVec3>,
     max_length: usize,
     sample_interval: f32,
     last_sample_time: f32,
@@ -20,7 +21,7 @@ impl ShipTrail {
         }
     }
 
-    pub fn update(&mut self, position: Vec3, time: f32) {
+    pub fn update(&mut self, position: DVec3, time: f32) {
         if time - self.last_sample_time >= self.sample_interval {
             self.positions.push(position);
             if self.positions.len() > self.max_length {
@@ -36,6 +37,7 @@ impl ShipTrail {
         renderer: &Renderer,
         view_matrix: &Mat4,
         projection_matrix: &Mat4,
+        origin: &DVec3,
     ) {
         if self.positions.len() < 2 {
             return;
@@ -51,8 +53,8 @@ impl ShipTrail {
 
             renderer.render_line(
                 framebuffer,
-                &self.positions[i],
-                &self.positions[i + 1],
+                &camera_relative(&self.positions[i], origin),
+                &camera_relative(&self.positions[i + 1], origin),
                 view_matrix,
                 projection_matrix,
                 color,
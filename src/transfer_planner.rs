@@ -0,0 +1,352 @@
+use crate::celestial_body::{CelestialBody, OrbitalParameters};
+use crate::gravity::level_gm;
+use nalgebra_glm::DVec3;
+use std::f64::consts::PI;
+
+/// Tolerancia relativa del tiempo de vuelo al resolver Lambert.
+const LAMBERT_TOLERANCE: f64 = 1e-10;
+const LAMBERT_MAX_ITERATIONS: usize = 200;
+/// Refinamientos de la fecha de salida sobre las efemérides reales.
+const WINDOW_REFINEMENTS: usize = 6;
+/// Búsqueda de la mejor transferencia de Lambert alrededor de la ventana de
+/// Hohmann: salida ±30 días (o un cuarto del período sinódico, si es menor)
+/// y tiempo de vuelo ±20%.
+const SEARCH_DEPARTURE_DAYS: f64 = 30.0;
+const SEARCH_FLIGHT_FRACTION: f64 = 0.2;
+const SEARCH_STEPS: usize = 12;
+
+/// Transferencia de Hohmann entre dos órbitas circulares coplanarias con los
+/// semiejes de origen y destino. Velocidades en unidades/día, tiempos en días.
+pub struct HohmannTransfer {
+    /// Impulso de salida: exceso sobre la velocidad orbital del origen.
+    pub departure_delta_v: f64,
+    /// Impulso de llegada para igualar la velocidad orbital del destino.
+    pub arrival_delta_v: f64,
+    pub time_of_flight: f64,
+    /// Ventaja angular que debe llevar el destino al salir (radianes).
+    pub phase_angle: f64,
+    /// Tiempo entre ventanas de lanzamiento consecutivas.
+    pub synodic_period: f64,
+}
+
+impl HohmannTransfer {
+    pub fn new(origin_radius: f64, target_radius: f64, gm: f64) -> Self {
+        let (r1, r2) = (origin_radius, target_radius);
+        let transfer_axis = r1 + r2;
+        let time_of_flight = PI * (transfer_axis.powi(3) / (8.0 * gm)).sqrt();
+
+        let target_motion = (gm / r2.powi(3)).sqrt();
+        let origin_motion = (gm / r1.powi(3)).sqrt();
+
+        Self {
+            departure_delta_v: ((gm / r1).sqrt() * ((2.0 * r2 / transfer_axis).sqrt() - 1.0)).abs(),
+            arrival_delta_v: ((gm / r2).sqrt() * (1.0 - (2.0 * r1 / transfer_axis).sqrt())).abs(),
+            time_of_flight,
+            phase_angle: (PI - target_motion * time_of_flight).rem_euclid(2.0 * PI),
+            synodic_period: 2.0 * PI / (origin_motion - target_motion).abs(),
+        }
+    }
+
+    /// Hohmann entre los semiejes actuales de dos órbitas.
+    pub fn between(origin: &OrbitalParameters, target: &OrbitalParameters, gm: f64, time: f64) -> Self {
+        Self::new(
+            origin.at_time(time).semi_major_axis,
+            target.at_time(time).semi_major_axis,
+            gm,
+        )
+    }
+
+    pub fn total_delta_v(&self) -> f64 {
+        self.departure_delta_v + self.arrival_delta_v
+    }
+}
+
+/// Transferencia entre las posiciones reales de dos cuerpos, resuelta con
+/// Lambert. Los excesos de velocidad son respecto a la órbita de cada cuerpo
+/// alrededor del central, sin contar el escape de su propia gravedad.
+pub struct LambertTransfer {
+    pub departure_time: f64,
+    pub time_of_flight: f64,
+    /// Exceso de velocidad a la salida (unidades/día); C3 es su cuadrado.
+    pub departure_excess: f64,
+    /// Diferencia de velocidad con el destino a la llegada (unidades/día).
+    pub arrival_excess: f64,
+    /// Cónica de la transferencia, relativa al cuerpo central.
+    pub orbit: OrbitalParameters,
+}
+
+impl LambertTransfer {
+    /// Resuelve la transferencia que sale del origen en `departure_time` y
+    /// alcanza el destino `time_of_flight` días después.
+    pub fn new(
+        origin: &OrbitalParameters,
+        target: &OrbitalParameters,
+        gm: f64,
+        departure_time: f64,
+        time_of_flight: f64,
+    ) -> Option<Self> {
+        let arrival_time = departure_time + time_of_flight;
+        let (r1, origin_velocity) = origin.get_state(departure_time);
+        let (r2, target_velocity) = target.get_state(arrival_time);
+        let (v1, v2) = solve_lambert(&r1, &r2, time_of_flight, gm)?;
+
+        Some(Self {
+            departure_time,
+            time_of_flight,
+            departure_excess: (v1 - origin_velocity).magnitude(),
+            arrival_excess: (v2 - target_velocity).magnitude(),
+            orbit: OrbitalParameters::from_state_vectors(&r1, &v1, gm, departure_time)?,
+        })
+    }
+
+    pub fn total_delta_v(&self) -> f64 {
+        self.departure_excess + self.arrival_excess
+    }
+
+    /// Puntos del tramo recorrido, de la salida a la llegada, relativos al
+    /// cuerpo central.
+    pub fn arc_points(&self, num_points: usize) -> Vec<DVec3> {
+        let steps = num_points.max(2) - 1;
+        (0..=steps)
+            .map(|i| {
+                let t = self.departure_time + self.time_of_flight * i as f64 / steps as f64;
+                self.orbit.get_position(t)
+            })
+            .collect()
+    }
+}
+
+/// Plan de transferencia entre dos cuerpos que orbitan el mismo central:
+/// presupuesto de Hohmann, próxima ventana y la transferencia real en ella.
+pub struct TransferPlan {
+    pub origin: usize,
+    pub target: usize,
+    pub hohmann: HohmannTransfer,
+    /// Salida de la próxima ventana de lanzamiento (días desde J2000): la de
+    /// la transferencia de Lambert si la hay y, si no, la fase de Hohmann.
+    pub departure_time: f64,
+    /// Transferencia de menor Δv entre las efemérides cerca de la ventana.
+    /// Las órbitas reales no son circulares ni coplanarias, y una salida a
+    /// 180° exactos del destino fuerza un cambio de plano muy caro.
+    pub lambert: Option<LambertTransfer>,
+}
+
+impl TransferPlan {
+    pub fn new(bodies: &[CelestialBody], origin: usize, target: usize, time: f64) -> Result<Self, String> {
+        let (origin_orbit, target_orbit, gm) = transfer_orbits(bodies, origin, target)?;
+        let hohmann = HohmannTransfer::between(origin_orbit, target_orbit, gm, time);
+        let window = next_window(origin_orbit, target_orbit, &hohmann, time);
+
+        let margin = SEARCH_DEPARTURE_DAYS.min(hohmann.synodic_period / 4.0);
+        let earliest = time.max(window - margin);
+        let latest = window + margin;
+        let mut lambert: Option<LambertTransfer> = None;
+        for i in 0..=SEARCH_STEPS {
+            let departure = earliest + (latest - earliest) * i as f64 / SEARCH_STEPS as f64;
+            for j in 0..=SEARCH_STEPS {
+                let fraction = 1.0 + SEARCH_FLIGHT_FRACTION * (2.0 * j as f64 / SEARCH_STEPS as f64 - 1.0);
+                let candidate = LambertTransfer::new(
+                    origin_orbit,
+                    target_orbit,
+                    gm,
+                    departure,
+                    hohmann.time_of_flight * fraction,
+                );
+                let better = candidate.as_ref().is_some_and(|c| {
+                    lambert.as_ref().is_none_or(|best| c.total_delta_v() < best.total_delta_v())
+                });
+                if better {
+                    lambert = candidate;
+                }
+            }
+        }
+        let departure_time = lambert.as_ref().map_or(window, |l| l.departure_time);
+
+        Ok(Self {
+            origin,
+            target,
+            hohmann,
+            departure_time,
+            lambert,
+        })
+    }
+}
+
+/// Órbitas de origen y destino y GM del cuerpo central que comparten, o por
+/// qué no se puede planificar una transferencia entre ellos.
+pub fn transfer_orbits(
+    bodies: &[CelestialBody],
+    origin: usize,
+    target: usize,
+) -> Result<(&OrbitalParameters, &OrbitalParameters, f64), String> {
+    let (origin_body, target_body) = (&bodies[origin], &bodies[target]);
+    if origin == target {
+        return Err("El origen y el destino son el mismo cuerpo".to_string());
+    }
+    if origin_body.parent_index != target_body.parent_index {
+        return Err(format!(
+            "{} y {} no orbitan el mismo cuerpo",
+            origin_body.name, target_body.name
+        ));
+    }
+
+    let origin_orbit = closed_orbit(origin_body)?;
+    let target_orbit = closed_orbit(target_body)?;
+    if origin_orbit.orbital_period == target_orbit.orbital_period {
+        return Err("Con el mismo período nunca se abre una ventana".to_string());
+    }
+
+    let gm = level_gm(bodies, origin_body.parent_index);
+    if gm <= 0.0 {
+        return Err("No se conoce la masa del cuerpo central".to_string());
+    }
+    Ok((origin_orbit, target_orbit, gm))
+}
+
+fn closed_orbit(body: &CelestialBody) -> Result<&OrbitalParameters, String> {
+    body.orbital_params
+        .as_ref()
+        .filter(|p| !p.is_open() && p.orbital_period > 0.0)
+        .ok_or_else(|| format!("{} no tiene una órbita cerrada", body.name))
+}
+
+/// Primer instante desde `time` en que el destino adelanta al origen el
+/// ángulo de fase de Hohmann, medido sobre las longitudes eclípticas reales.
+pub fn next_window(
+    origin: &OrbitalParameters,
+    target: &OrbitalParameters,
+    hohmann: &HohmannTransfer,
+    time: f64,
+) -> f64 {
+    let longitude = |params: &OrbitalParameters, t: f64| {
+        let position = params.get_position(t);
+        (-position.z).atan2(position.x)
+    };
+    let phase = |t: f64| (longitude(target, t) - longitude(origin, t)).rem_euclid(2.0 * PI);
+    let relative_motion = 2.0 * PI / target.orbital_period - 2.0 * PI / origin.orbital_period;
+
+    // Estimación con movimientos medios y corrección sobre las efemérides.
+    let remaining = if relative_motion < 0.0 {
+        (phase(time) - hohmann.phase_angle).rem_euclid(2.0 * PI)
+    } else {
+        (hohmann.phase_angle - phase(time)).rem_euclid(2.0 * PI)
+    };
+    let mut departure = time + remaining / relative_motion.abs();
+    for _ in 0..WINDOW_REFINEMENTS {
+        let error = (hohmann.phase_angle - phase(departure) + PI).rem_euclid(2.0 * PI) - PI;
+        departure += error / relative_motion;
+    }
+
+    if departure < time {
+        departure += hohmann.synodic_period;
+    }
+    departure
+}
+
+/// Problema de Lambert: velocidades de salida y llegada de la cónica que
+/// une `r1` y `r2` en `time_of_flight` días alrededor de una masa `gm`.
+///
+/// Variables universales con bisección en ψ (Vallado, alg. 58), sin vueltas
+/// completas y siempre en sentido prograde (antihorario visto desde +Y, el
+/// polo de la eclíptica). `None` si no converge o los puntos están alineados
+/// con el centro, donde el plano de la transferencia queda indefinido.
+pub fn solve_lambert(r1: &DVec3, r2: &DVec3, time_of_flight: f64, gm: f64) -> Option<(DVec3, DVec3)> {
+    let (radius1, radius2) = (r1.magnitude(), r2.magnitude());
+    if time_of_flight <= 0.0 || gm <= 0.0 || radius1 < 1e-9 || radius2 < 1e-9 {
+        return None;
+    }
+
+    let cos_angle = (r1.dot(r2) / (radius1 * radius2)).clamp(-1.0, 1.0);
+    let direction = if r1.cross(r2).y >= 0.0 { 1.0 } else { -1.0 };
+    let a = direction * (radius1 * radius2 * (1.0 + cos_angle)).sqrt();
+    if a.abs() < 1e-9 * (radius1 + radius2) || (1.0 - cos_angle) < 1e-12 {
+        return None;
+    }
+
+    let sqrt_mu = gm.sqrt();
+    let (mut low, mut high) = (-4.0 * PI, 4.0 * PI * PI);
+    let mut psi = 0.0;
+    let mut y = 0.0;
+    let mut converged = false;
+
+    for _ in 0..LAMBERT_MAX_ITERATIONS {
+        let (c2, c3) = stumpff(psi);
+        y = radius1 + radius2 + a * (psi * c3 - 1.0) / c2.sqrt();
+        if a > 0.0 && y < 0.0 {
+            // ψ demasiado pequeño: la solución está más arriba.
+            low = psi;
+            psi = (low + high) / 2.0;
+            continue;
+        }
+
+        let chi = (y / c2).sqrt();
+        let flight_time = (chi.powi(3) * c3 + a * y.sqrt()) / sqrt_mu;
+        if (flight_time - time_of_flight).abs() < LAMBERT_TOLERANCE * time_of_flight {
+            converged = true;
+            break;
+        }
+        if flight_time <= time_of_flight {
+            low = psi;
+        } else {
+            high = psi;
+        }
+        psi = (low + high) / 2.0;
+    }
+    if !converged {
+        return None;
+    }
+
+    let f = 1.0 - y / radius1;
+    let g = a * (y / gm).sqrt();
+    let g_dot = 1.0 - y / radius2;
+    Some(((r2 - r1 * f) / g, (r2 * g_dot - r1) / g))
+}
+
+/// Funciones de Stumpff `c2(ψ)` y `c3(ψ)`.
+fn stumpff(psi: f64) -> (f64, f64) {
+    if psi > 1e-6 {
+        let s = psi.sqrt();
+        ((1.0 - s.cos()) / psi, (s - s.sin()) / (s * psi))
+    } else if psi < -1e-6 {
+        let s = (-psi).sqrt();
+        ((1.0 - s.cosh()) / psi, (s.sinh() - s) / (s * -psi))
+    } else {
+        (0.5, 1.0 / 6.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lambert_matches_hohmann_for_coplanar_half_orbit() {
+        let (gm, r1, r2) = (2.96e-4, 1.0, 1.524);
+        let hohmann = HohmannTransfer::new(r1, r2, gm);
+
+        // A 180° exactos el plano de Lambert queda indefinido: se apunta a
+        // un destino casi opuesto en el plano de la eclíptica (y = 0).
+        let angle = PI - 1e-6;
+        let origin = DVec3::new(r1, 0.0, 0.0);
+        let target = DVec3::new(r2 * angle.cos(), 0.0, -r2 * angle.sin());
+        let (v1, v2) = solve_lambert(&origin, &target, hohmann.time_of_flight, gm)
+            .expect("la transferencia debe converger");
+
+        let departure_delta_v = v1.magnitude() - (gm / r1).sqrt();
+        let arrival_delta_v = (gm / r2).sqrt() - v2.magnitude();
+        let tolerance = 1e-4 * (gm / r1).sqrt();
+        assert!((departure_delta_v - hohmann.departure_delta_v).abs() < tolerance);
+        assert!((arrival_delta_v - hohmann.arrival_delta_v).abs() < tolerance);
+
+        // Sale del periapsis y llega al apoapsis: velocidades perpendiculares al radio.
+        assert!(v1.normalize().dot(&origin.normalize()).abs() < 1e-4);
+        assert!(v2.normalize().dot(&target.normalize()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn lambert_rejects_exactly_opposite_points() {
+        let origin = DVec3::new(1.0, 0.0, 0.0);
+        let target = DVec3::new(-1.5, 0.0, 0.0);
+        assert!(solve_lambert(&origin, &target, 250.0, 2.96e-4).is_none());
+    }
+}
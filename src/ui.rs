@@ -1,23 +1,39 @@
 use raylib::prelude::*;
-use crate::celestial_body::CelestialBody;
+use crate::celestial_body::{CelestialBody, OrbitalParameters, RotationModel};
+use crate::epoch::CalendarDate;
+use crate::flight::{to_meters_per_second, GravityField, NewtonianShip, METERS_PER_UNIT};
+use crate::transfer_planner::TransferPlan;
 
 pub struct GameUI;
 
 impl GameUI {
+    /// Panel con los datos del cuerpo y de su órbita (la de Kepler o la
+    /// osculante de la integración). Devuelve la coordenada y bajo el panel.
     pub fn draw_planet_info(
         d: &mut RaylibDrawHandle,
         body: &CelestialBody,
+        orbit: Option<&OrbitalParameters>,
         distance: f32,
         camera_speed: f32,
-    ) {
+        time: f64,
+    ) -> i32 {
         let panel_x = 10;
         let panel_y = 200;
-        
+        let physical = &body.physical;
+
+        let orbit = orbit.map(|params| orbit_summary(params, time)).unwrap_or_default();
+        let description = wrap_text(&physical.description, 44);
+        let has_eta = camera_speed > 0.1;
+        let panel_height = 210
+            + orbit.len() as i32 * 20
+            + description.len() as i32 * 16
+            + if has_eta { 20 } else { 0 };
+
         d.draw_rectangle(
             panel_x - 5,
             panel_y - 5,
-            250,
-            120,
+            320,
+            panel_height,
             Color::new(0, 0, 0, 180),
         );
 
@@ -37,14 +53,76 @@ impl GameUI {
             Color::WHITE,
         );
         d.draw_text(
-            &format!("Radio: {:.1} u", body.radius),
+            &format!(
+                "Radio: {:.1} u ({:.0} km ecuat. / {:.0} km polar)",
+                body.radius, physical.equatorial_radius, physical.polar_radius
+            ),
             panel_x,
             panel_y + 65,
             14,
             Color::WHITE,
         );
+        d.draw_text(
+            &format!("Masa: {:.3e} kg  GM: {:.4e} km3/s2", physical.mass(), physical.gm),
+            panel_x,
+            panel_y + 85,
+            14,
+            Color::WHITE,
+        );
+        d.draw_text(
+            &format!(
+                "Densidad: {:.2} g/cm3  Albedo: {:.2}",
+                physical.mean_density, physical.albedo
+            ),
+            panel_x,
+            panel_y + 105,
+            14,
+            Color::WHITE,
+        );
+        d.draw_text(
+            &format!(
+                "Temperatura: {:.0} K ({:.0} °C)",
+                physical.surface_temperature,
+                physical.surface_temperature - 273.15
+            ),
+            panel_x,
+            panel_y + 125,
+            14,
+            Color::WHITE,
+        );
+        let atmosphere = if physical.atmosphere.is_empty() {
+            "sin atmósfera"
+        } else {
+            physical.atmosphere.as_str()
+        };
+        d.draw_text(
+            &format!("Atmósfera: {}", atmosphere),
+            panel_x,
+            panel_y + 145,
+            14,
+            Color::WHITE,
+        );
+        d.draw_text(
+            &rotation_summary(&body.rotation),
+            panel_x,
+            panel_y + 165,
+            14,
+            Color::WHITE,
+        );
 
-        if camera_speed > 0.1 {
+        let mut y = panel_y + 185;
+        for line in &orbit {
+            d.draw_text(line, panel_x, y, 14, Color::SKYBLUE);
+            y += 20;
+        }
+
+        y += 5;
+        for line in &description {
+            d.draw_text(line, panel_x, y, 12, Color::LIGHTGRAY);
+            y += 16;
+        }
+
+        if has_eta {
             let eta = distance / camera_speed;
             let eta_text = if eta < 60.0 {
                 format!("ETA: {:.0}s", eta)
@@ -53,8 +131,243 @@ impl GameUI {
             } else {
                 format!("ETA: {:.1}h", eta / 3600.0)
             };
-            d.draw_text(&eta_text, panel_x, panel_y + 85, 14, Color::ORANGE);
+            d.draw_text(&eta_text, panel_x, y + 4, 14, Color::ORANGE);
         }
+
+        panel_y - 5 + panel_height
     }
 
-}
\ No newline at end of file
+    /// Estado del vuelo newtoniano: cuerpo dominante, empuje, altitudes del
+    /// periapsis y el apoapsis previstos y velocidad relativa.
+    pub fn draw_flight_status(
+        d: &mut RaylibDrawHandle,
+        ship: &NewtonianShip,
+        field: &GravityField,
+        reference_name: &str,
+        x: i32,
+        y: i32,
+    ) {
+        let surface = field.surface_radius(ship.reference);
+        let altitude = |radius: f64| format!("{:.0} km", (radius - surface) * METERS_PER_UNIT / 1000.0);
+
+        let orbit = if ship.landed {
+            "ATERRIZADO".to_string()
+        } else {
+            match ship.orbit(field) {
+                Some(orbit) => format!(
+                    "Pe: {}  Ap: {}",
+                    altitude(orbit.periapsis_distance()),
+                    orbit
+                        .apoapsis_distance()
+                        .map(altitude)
+                        .unwrap_or_else(|| "- (escape)".to_string())
+                ),
+                None => "Sin órbita".to_string(),
+            }
+        };
+
+        let speed = to_meters_per_second(ship.velocity.magnitude());
+        let speed = if speed < 1000.0 {
+            format!("{:.0} m/s", speed)
+        } else {
+            format!("{:.2} km/s", speed / 1000.0)
+        };
+
+        d.draw_rectangle(x - 5, y - 5, 330, 65, Color::new(0, 0, 0, 180));
+        d.draw_text(
+            &format!("VUELO NEWTONIANO - {}", reference_name),
+            x,
+            y,
+            16,
+            Color::GREEN,
+        );
+        d.draw_text(&orbit, x, y + 20, 14, Color::SKYBLUE);
+        d.draw_text(
+            &format!("Vel. relativa: {}  Empuje: {:.1} m/s2", speed, ship.thrust),
+            x,
+            y + 38,
+            14,
+            Color::SKYBLUE,
+        );
+    }
+
+    /// Panel del planificador de transferencias: pide el destino mientras
+    /// solo hay origen, y si no muestra el plan o por qué no se puede calcular.
+    pub fn draw_transfer_plan(
+        d: &mut RaylibDrawHandle,
+        bodies: &[CelestialBody],
+        origin: usize,
+        plan: Option<&Result<TransferPlan, String>>,
+        time: f64,
+        x: i32,
+        y: i32,
+    ) {
+        let km_s = |speed: f64| to_meters_per_second(speed) / 1000.0;
+
+        let (title, lines) = match plan {
+            None => (
+                format!("TRANSFERENCIA desde {}", bodies[origin].name),
+                vec!["Selecciona el destino y pulsa Y".to_string()],
+            ),
+            Some(Err(e)) => (format!("TRANSFERENCIA desde {}", bodies[origin].name), vec![e.clone()]),
+            Some(Ok(plan)) => {
+                let hohmann = &plan.hohmann;
+                let mut lines = vec![
+                    format!(
+                        "Hohmann: {:.2} + {:.2} = {:.2} km/s",
+                        km_s(hohmann.departure_delta_v),
+                        km_s(hohmann.arrival_delta_v),
+                        km_s(hohmann.total_delta_v())
+                    ),
+                    format!(
+                        "Vuelo: {:.0} días  Fase: {:.1}°",
+                        hohmann.time_of_flight,
+                        hohmann.phase_angle.to_degrees()
+                    ),
+                    format!(
+                        "Ventana: {} (en {:.0} días)",
+                        CalendarDate::from_simulation_time(plan.departure_time),
+                        plan.departure_time - time
+                    ),
+                    format!("Período sinódico: {:.0} días", hohmann.synodic_period),
+                ];
+                lines.push(match &plan.lambert {
+                    Some(lambert) => format!(
+                        "Lambert: {:.2} + {:.2} km/s  {:.0} días  C3: {:.1}",
+                        km_s(lambert.departure_excess),
+                        km_s(lambert.arrival_excess),
+                        lambert.time_of_flight,
+                        km_s(lambert.departure_excess).powi(2)
+                    ),
+                    None => "Lambert: sin solución".to_string(),
+                });
+                lines.push("[INICIO] Ir a la salida  [Y] Nuevo origen".to_string());
+                (
+                    format!("TRANSFERENCIA {} -> {}", bodies[plan.origin].name, bodies[plan.target].name),
+                    lines,
+                )
+            }
+        };
+
+        d.draw_rectangle(x - 5, y - 5, 340, 30 + lines.len() as i32 * 18, Color::new(0, 0, 0, 180));
+        d.draw_text(&title, x, y, 16, Color::ORANGE);
+        for (i, line) in lines.iter().enumerate() {
+            d.draw_text(line, x, y + 22 + i as i32 * 18, 14, Color::WHITE);
+        }
+    }
+
+    /// Muestra las acciones disponibles para el cuerpo seleccionado con el ratón.
+    pub fn draw_selection_actions(d: &mut RaylibDrawHandle, locked_on: bool, panel_y: i32) {
+        let panel_x = 10;
+
+        d.draw_rectangle(panel_x - 5, panel_y - 5, 250, 45, Color::new(0, 0, 0, 180));
+        d.draw_text("[ENTER] Viajar", panel_x, panel_y, 14, Color::SKYBLUE);
+
+        let (lock_text, lock_color) = if locked_on {
+            ("[B] Soltar objetivo", Color::GREEN)
+        } else {
+            ("[B] Fijar objetivo", Color::SKYBLUE)
+        };
+        d.draw_text(lock_text, panel_x, panel_y + 20, 14, lock_color);
+    }
+
+    /// Cuadro de entrada para saltar a una fecha (`AAAA-MM-DD [HH:MM[:SS]]`).
+    pub fn draw_date_input(
+        d: &mut RaylibDrawHandle,
+        text: &str,
+        error: Option<&str>,
+        screen_width: i32,
+        screen_height: i32,
+    ) {
+        let panel_w = 360;
+        let panel_h = 90;
+        let panel_x = screen_width / 2 - panel_w / 2;
+        let panel_y = screen_height / 2 - panel_h / 2;
+
+        d.draw_rectangle(panel_x, panel_y, panel_w, panel_h, Color::new(0, 0, 0, 200));
+        d.draw_rectangle_lines(panel_x, panel_y, panel_w, panel_h, Color::SKYBLUE);
+
+        d.draw_text("IR A FECHA (UTC)", panel_x + 10, panel_y + 10, 16, Color::YELLOW);
+        d.draw_text(&format!("{}_", text), panel_x + 10, panel_y + 35, 18, Color::WHITE);
+
+        match error {
+            Some(message) => d.draw_text(message, panel_x + 10, panel_y + 65, 12, Color::RED),
+            None => d.draw_text(
+                "AAAA-MM-DD [HH:MM[:SS]]  ENTER aceptar / J cancelar",
+                panel_x + 10,
+                panel_y + 65,
+                12,
+                Color::GRAY,
+            ),
+        }
+    }
+
+}
+
+/// Lecturas de la órbita osculante en `time`.
+fn orbit_summary(params: &OrbitalParameters, time: f64) -> Vec<String> {
+    let params = params.at_time(time);
+    let (_, velocity) = params.get_state(time);
+    let true_anomaly = params.true_anomaly(time).unwrap_or(0.0).to_degrees();
+
+    let apoapsis = match params.apoapsis_distance() {
+        Some(apoapsis) => format!("{:.0} u", apoapsis),
+        None => "- (abierta)".to_string(),
+    };
+
+    vec![
+        format!(
+            "Periapsis: {:.0} u  Apoapsis: {}",
+            params.periapsis_distance(),
+            apoapsis
+        ),
+        format!(
+            "e: {:.4}  Anomalía verdadera: {:.1}°",
+            params.eccentricity,
+            true_anomaly.rem_euclid(360.0)
+        ),
+        format!(
+            "Vel. orbital: {:.1} u/día  Energía: {:.3e}",
+            velocity.magnitude(),
+            params.specific_energy()
+        ),
+    ]
+}
+
+/// Período y sentido de giro y oblicuidad del eje.
+fn rotation_summary(rotation: &RotationModel) -> String {
+    let spin = if rotation.tidally_locked {
+        "síncrona".to_string()
+    } else if rotation.period == 0.0 {
+        "sin giro".to_string()
+    } else {
+        let period = rotation.period.abs();
+        let period = if period < 2.0 {
+            format!("{:.1} h", period * 24.0)
+        } else {
+            format!("{:.1} d", period)
+        };
+        if rotation.period < 0.0 { format!("{} retrógrada", period) } else { period }
+    };
+    format!("Rotación: {}  Oblicuidad: {:.1}°", spin, rotation.obliquity.to_degrees())
+}
+
+/// Parte un texto en líneas de como mucho `max_chars` caracteres por palabras.
+fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
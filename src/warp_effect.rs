@@ -1,12 +1,12 @@
-use nalgebra_glm::Vec3;
+use nalgebra_glm::DVec3;
 use crate::framebuffer::{Framebuffer, Color};
 
 pub struct WarpEffect {
     pub active: bool,
     pub progress: f32,      // 0.0 -> 1.0
     pub duration: f32,      // duración en segundos
-    start_pos: Vec3,
-    end_pos: Vec3,
+    start_pos: DVec3,
+    end_pos: DVec3,
     elapsed: f32,
 }
 
@@ -16,14 +16,14 @@ impl WarpEffect {
             active: false,
             progress: 0.0,
             duration: 1.5,
-            start_pos: Vec3::zeros(),
-            end_pos: Vec3::zeros(),
+            start_pos: DVec3::zeros(),
+            end_pos: DVec3::zeros(),
             elapsed: 0.0,
         }
     }
 
     /// Inicia un warp desde una posición a otra
-    pub fn start_warp(&mut self, from: Vec3, to: Vec3, duration: f32) {
+    pub fn start_warp(&mut self, from: DVec3, to: DVec3, duration: f32) {
         self.active = true;
         self.progress = 0.0;
         self.elapsed = 0.0;
@@ -33,7 +33,7 @@ impl WarpEffect {
     }
 
     /// Actualiza el estado del warp
-    pub fn update(&mut self, delta_time: f32) -> Option<Vec3> {
+    pub fn update(&mut self, delta_time: f32) -> Option<DVec3> {
         if !self.active {
             return None;
         }
@@ -43,7 +43,7 @@ impl WarpEffect {
 
         // Curva de ease-in-out
         let t = self.smooth_step(self.progress);
-        let current_pos = self.start_pos + (self.end_pos - self.start_pos) * t;
+        let current_pos = self.start_pos + (self.end_pos - self.start_pos) * t as f64;
 
         if self.progress >= 1.0 {
             self.active = false;